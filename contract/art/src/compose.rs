@@ -0,0 +1,97 @@
+use crate::svg::Element;
+
+/// A small layered SVG composer. Layers are drawn bottom to top by `z`, layers with equal `z`
+/// in the order they were added. Shared definitions (gradients, glyph groups, filters) go into a
+/// single `<defs>` block after the layers, in the order they were added.
+pub struct Composition {
+    root: Element,
+    /// Elements written before every layer, such as `<title>` and `<desc>`.
+    head: Vec<Element>,
+    layers: Vec<Layer>,
+    defs: Vec<String>,
+}
+
+pub struct Layer {
+    pub z: u8,
+    /// SVG transform list applied to the whole layer.
+    pub transform: Option<String>,
+    pub markup: String,
+}
+
+pub const Z_BACKGROUND: u8 = 0;
+pub const Z_HALO: u8 = 5;
+pub const Z_CRUST: u8 = 10;
+pub const Z_GLITCH: u8 = 30;
+pub const Z_INSCRIPTION: u8 = 35;
+pub const Z_OVERLAY: u8 = 40;
+
+impl Composition {
+    /// `root` is the `<svg>` element, without children.
+    pub fn new(root: Element) -> Self {
+        Self {
+            root,
+            head: Vec::new(),
+            layers: Vec::new(),
+            defs: Vec::new(),
+        }
+    }
+
+    pub fn head(&mut self, element: Element) {
+        self.head.push(element);
+    }
+
+    pub fn layer(&mut self, z: u8, markup: String, transform: Option<String>) {
+        self.layers.push(Layer {
+            z,
+            transform,
+            markup,
+        });
+    }
+
+    pub fn def(&mut self, markup: String) {
+        self.defs.push(markup);
+    }
+
+    pub fn render(mut self) -> String {
+        self.layers.sort_by_key(|layer| layer.z);
+        let mut root = self.root;
+        for element in self.head {
+            root = root.child(element);
+        }
+        for layer in self.layers {
+            root = match layer.transform {
+                Some(transform) => root.child(
+                    Element::new("g")
+                        .attr("transform", transform)
+                        .raw(layer.markup),
+                ),
+                None => root.raw(layer.markup),
+            };
+        }
+        let mut defs = Element::new("defs");
+        for def in self.defs {
+            defs = defs.raw(def);
+        }
+        root.child(defs).render()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut composition = Composition::new(Element::new("svg"));
+        composition.layer(Z_OVERLAY, "<o/>".to_string(), None);
+        composition.layer(Z_BACKGROUND, "<b/>".to_string(), None);
+        composition.layer(Z_GLITCH, "<g1/>".to_string(), Some("rotate(5)".to_string()));
+        composition.layer(Z_GLITCH, "<g2/>".to_string(), None);
+        composition.def("<d/>".to_string());
+        composition.head(Element::new("title").text("t"));
+        assert_eq!(
+            composition.render(),
+            r#"<svg><title>t</title><b/><g transform="rotate(5)"><g1/></g><g2/><o/><defs><d/></defs></svg>"#
+        );
+    }
+}
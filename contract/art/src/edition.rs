@@ -0,0 +1,78 @@
+//! Editions of the mooncake, with the generator their tokens are minted with. The contract mints
+//! from this table and the preview renders from it, so both agree on what an edition looks like.
+use crate::facai_gen::{LayerChoice, PaletteChoice};
+use crate::generator::Style;
+
+pub struct Edition {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Version of the `generator::ArtGenerator` new tokens are rendered with.
+    pub generator: u8,
+    pub style: Style,
+    /// Block timestamp, in nanoseconds, until which tokens show a placeholder instead of their
+    /// art. `None` for editions shown as minted.
+    pub reveal_at: Option<u64>,
+}
+
+impl Edition {
+    pub fn is_sealed_at(&self, timestamp: u64) -> bool {
+        self.reveal_at
+            .is_some_and(|reveal_at| timestamp < reveal_at)
+    }
+}
+
+pub const EDITIONS: &[Edition] = &[
+    Edition {
+        id: "2022",
+        title: "恭喜发财",
+        description: "Gong Xi Fa Cai. Mooncake NFT 2022 Edition.",
+        generator: crate::facai_gen::FACAI_V1.version,
+        style: Style::CLASSIC,
+        reveal_at: None,
+    },
+    Edition {
+        id: "2027",
+        title: "花好月圆",
+        description: "Hua Hao Yue Yuan. Mooncake NFT 2027 Edition, revealed on Mid-Autumn night.",
+        generator: crate::facai_gen::FACAI_V2.version,
+        style: Style {
+            palette: PaletteChoice::Seeded,
+            layers: LayerChoice::Seeded,
+            animated: true,
+            described: true,
+        },
+        // Mid-Autumn Festival, 2027-09-15 00:00 in China (UTC+8).
+        reveal_at: Some(1_820_937_600_000_000_000),
+    },
+];
+
+pub fn get(id: &str) -> Option<&'static Edition> {
+    EDITIONS.iter().find(|edition| edition.id == id)
+}
+
+/// Most recently added edition.
+pub fn latest() -> &'static Edition {
+    EDITIONS.last().unwrap()
+}
+
+/// Edition of a generated token, whose id is "<edition>-<seed>" or "<edition>-<serial>".
+pub fn of_token(token_id: &str) -> Option<&'static Edition> {
+    get(token_id.split_once('-')?.0)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_of_token() {
+        assert_eq!(of_token("2022-abc").map(|edition| edition.id), Some("2022"));
+        assert!(of_token("1999-abc").is_none());
+        assert!(of_token("custom").is_none());
+        let edition = get("2027").unwrap();
+        assert!(edition.is_sealed_at(edition.reveal_at.unwrap() - 1));
+        assert!(!edition.is_sealed_at(edition.reveal_at.unwrap()));
+        assert!(!get("2022").unwrap().is_sealed_at(0));
+    }
+}
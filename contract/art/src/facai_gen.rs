@@ -0,0 +1,1303 @@
+use crate::compose::{
+    Composition, Z_BACKGROUND, Z_CRUST, Z_GLITCH, Z_HALO, Z_INSCRIPTION, Z_OVERLAY,
+};
+use crate::facai_text::{self, Description};
+use crate::generator::{ArtGenerator, Attributes, Extras, Seed, Style};
+use crate::palette::{self, Palette};
+use crate::svg::Element;
+use serde::Serialize;
+
+/// Static layers drawn in a `palette::Palette`. Drawn in `palette::CLASSIC`, one element per
+/// line and in this order, they are the static markup of the original 2022 generator, see
+/// `legacy`.
+fn background_none(_: &Palette) -> Vec<Element> {
+    vec![Element::new("path")
+        .attr("fill", "none")
+        .attr("d", "M0 0h1080v1080H0z")]
+}
+
+fn crust(palette: &Palette) -> Vec<Element> {
+    vec![
+        Element::new("path")
+            .attr("d", "M462 944c-13 4-27 7-42 7-43 0-79-21-97-51V695c17 30 54 51 97 51 15 0 29-3 42-8v206ZM846 639a99 99 0 0 0 80-102c8-13 13-29 14-45v188a108 108 0 0 1-13 62c3 37-18 74-55 93-8 5-17 8-25 10l-1-206Z")
+            .attr("fill", palette.crust)
+            .attr("stroke", palette.crust_stroke)
+            .attr("stroke-width", 4),
+        Element::new("path")
+            .attr("d", "M847 845a104 104 0 0 1-110 73V711c19 2 40-2 58-12 26-13 44-36 51-61l1 206ZM737 917a103 103 0 0 1-137 32V745c20 12 46 17 73 11s50-22 64-43v205ZM600 950c-18 14-40 23-66 23-28 0-54-12-72-29V738c18 18 44 29 72 29 25 0 48-8 66-23v206ZM322 900c-48-2-87-41-93-89V605c5 49 45 87 93 90v205ZM229 810a101 101 0 0 1-61-98c-7-13-12-28-12-44h0v-1a107 107 0 0 1 0-8V465c1 15 5 29 12 42v4c0 42 25 78 61 93v206Z")
+            .attr("fill", palette.crust)
+            .attr("stroke", palette.crust_stroke)
+            .attr("stroke-width", 4),
+        Element::new("path")
+            .attr("d", "M528 3074c18-15 43-24 69-23 31 1 58 15 76 37 19-4 40-2 60 7 23 10 41 28 52 49a99 99 0 0 1 89 79 98 98 0 0 1 59 106 105 105 0 0 1 0 104c2 36-18 73-55 93-8 4-17 7-26 9-7 25-25 47-51 61-19 10-39 14-58 12-14 21-37 37-65 43-26 6-52 1-73-11-17 14-40 23-65 23-28 0-54-11-72-29-13 5-28 7-43 7-42 0-79-20-97-51-48-2-87-40-93-89a101 101 0 0 1-61-98 103 103 0 0 1 17-121c2-48 36-87 81-96 8-24 27-47 53-61 21-12 43-17 64-15 13-21 35-38 61-45 28-7 56-3 78 9Z")
+            .attr("fill", "url(#a)")
+            .attr("transform", "translate(-7 -2895)"),
+        Element::new("path")
+            .attr("d", "M520 207c15-14 38-22 63-22 32 0 59 15 72 36 16-13 43-17 69-7 24 10 41 28 45 46 25-1 52 9 72 31 15 15 24 34 27 52a71 71 0 0 1 38 103c13 10 22 25 21 41 0 24-18 44-43 52 15 18 19 43 8 64a57 57 0 0 1-49 30c-6 25-25 49-52 64-24 14-49 18-71 14-6 26-30 46-58 46-20 0-38-10-48-25a90 90 0 0 1-75 33c-35 0-65-18-78-43a59 59 0 0 1-48 24c-32 0-58-24-59-55-26 7-59-3-83-28a95 95 0 0 1-28-72c-22-6-38-20-42-40-3-17 5-34 19-46a62 62 0 0 1-29-53c0-22 13-41 33-52a57 57 0 0 1 57-86c2-24 24-52 58-68 21-10 43-15 61-13 10-18 30-33 55-39 24-5 48 1 65 13Zm-47 493a404 404 0 0 0 135 6 96 96 0 0 1-66 28c-28-1-55-15-69-34Zm229-18c-6 22-25 44-49 44-16 0-27-10-35-22 30-4 58-12 84-22Zm-321-15c25 13 54 24 84 31-9 11-21 19-37 19-27 0-45-25-47-50Zm426-52c-6 21-14 46-37 59-20 10-42 8-60 5 38-16 71-38 97-64Zm-45-4c-15 6-32 8-47 10-92 13-185 20-278 25l-26 2c44 19 95 30 150 29 79-2 151-27 201-66Zm-481-34c22 33 50 61 90 82-24 5-51 5-71-16-18-17-20-45-19-66Zm443-239c-58-2-222 14-333 25-33 4-36 15-33 46 6 55 9 111 16 165 2 14 2 30 12 39 12 10 31 5 47 4 92-9 181-13 273-24 14-2 34-6 42-17 11-15 8-36 7-55-2-49-7-98-15-146-2-13-3-37-16-37Zm134 202c10 15 11 31 3 48-9 15-32 22-50 23 20-21 36-45 47-71ZM325 367c-30 33-47 72-46 113 1 50 26 95 68 129l-3-29c-9-58-18-116-21-174 0-13-1-27 2-39Zm444-23c9 36 14 96 18 173 1 24 8 52-2 73 33-33 52-74 51-118-1-49-26-94-67-128ZM252 505c4 25 8 40 22 62-19-5-34-6-37-22-3-15 3-29 15-40Zm618-48c11 8 23 19 23 31-1 19-13 35-32 43a181 181 0 0 0 9-74Zm-610-40a181 181 0 0 0-8 86 54 54 0 0 1-25-45c1-18 17-32 33-41Zm575-53c19 7 39 19 45 39 4 15-4 30-11 43-5-30-16-57-34-82Zm-424-94c-71 30-125 81-148 140-9-16-15-39-3-54 12-16 31-23 49-20 2-22 22-46 52-60 17-8 34-7 50-6Zm415 81c-21-29-41-43-75-63 21-1 45 8 64 27 13 14 8 21 11 36Zm-121-47c-44-19-95-29-150-29-80 2-152 27-202 66l21-3c97-10 251-33 331-34Zm38-20c-29-17-55-26-91-34 15-9 36-10 57-2s30 19 34 36Zm-222-42c-36 4-70 12-101 25 9-16 25-33 46-37 20-4 40 3 55 12Zm122 6a405 405 0 0 0-114-6 82 82 0 0 1 114 6Z")
+            .attr("fill", palette.pattern),
+        Element::new("path")
+            .attr("d", "M516 3101c15-14 38-22 64-22 31 1 59 15 72 36 16-13 43-17 69-6 24 9 40 27 45 46 24-2 51 8 72 30 15 15 24 34 27 52a71 71 0 0 1 38 103c13 10 21 25 21 42 0 23-18 43-44 51 15 18 20 44 8 65a57 57 0 0 1-49 29c-6 25-24 49-51 65-24 13-50 17-72 13-5 26-29 46-57 46-20 0-38-10-49-25a90 90 0 0 1-74 34c-36-1-66-19-78-44a59 59 0 0 1-48 25c-32 0-58-25-59-56-27 7-60-3-84-28a95 95 0 0 1-27-72c-22-5-39-20-42-40-3-17 4-34 18-46a62 62 0 0 1-29-52c1-23 14-42 33-53a57 57 0 0 1 58-85c2-25 24-52 57-69 21-10 43-14 62-13 10-18 30-33 54-38 25-5 48 0 65 12Zm-46 493a404 404 0 0 0 134 6 96 96 0 0 1-65 28c-29 0-56-15-69-34Zm229-18c-6 22-25 45-49 45-16 0-27-11-36-23 30-4 59-12 85-22Zm-321-15c25 13 53 24 83 31-9 11-21 19-36 19-27 0-45-24-47-50Zm426-52c-6 21-15 46-38 59-19 11-41 8-60 5 38-16 71-38 98-64Zm-46-4c-14 6-31 8-46 10-92 14-185 21-278 25l-26 2c43 19 95 30 149 29 80-1 151-26 201-66Zm-479-30c22 33 54 61 93 83-23 4-55 0-76-21-17-17-18-42-17-62Zm441-243c-58-2-221 14-332 25-33 4-36 15-33 46 6 55 9 111 16 166 2 13 1 29 12 38 12 10 31 5 47 4 92-9 181-12 273-24 13-2 34-6 42-17 11-14 8-36 7-54-2-49-7-98-15-147-3-13-3-36-17-37Zm134 202c10 15 12 31 3 48-8 15-31 22-49 23 20-21 36-45 46-71Zm-532-173c-30 33-47 72-46 114 1 49 26 94 68 128l-3-29c-10-57-18-115-21-174-1-12-2-26 2-39Zm444-23c9 36 14 96 17 173 2 24 9 52-1 73 33-33 52-74 51-118-1-49-26-93-67-128Zm-517 161c4 25 13 49 27 71-19-5-39-15-42-31-3-14 3-29 15-40Zm618-48c11 9 22 19 22 32 0 18-13 34-31 42a181 181 0 0 0 9-74Zm-610-40a181 181 0 0 0-8 86 54 54 0 0 1-25-45c0-18 17-31 33-41Zm575-53c19 7 39 19 44 40 4 14-3 29-11 42-4-29-16-57-33-82Zm-425-93c-71 30-124 80-148 139-9-16-14-38-3-54 12-16 31-23 50-20 2-21 22-46 52-60 16-8 34-7 49-5Zm424 92c-20-29-49-54-84-74 22-2 46 8 65 26 13 14 17 32 19 48Zm-130-58c-43-20-95-30-150-29-79 1-151 26-201 66l21-4c97-9 250-32 330-33Zm45-17c-28-17-62-30-97-38 14-8 36-10 57-2s36 24 40 40Zm-228-45c-36 3-70 12-102 24 10-16 26-32 46-37 21-4 41 3 56 13Zm121 5a405 405 0 0 0-113-6 82 82 0 0 1 113 6Z")
+            .attr("fill", "url(#b)")
+            .attr("transform", "translate(-6 -2907)"),
+        Element::new("path")
+            .attr("d", "M207 423c-4 8-2 15-8 18-5 2-6-9-3-19 3-14 22-28 24-22 1 6-8 15-13 23ZM337 254c-24 14-43 34-46 30-4-4 13-26 34-38 31-17 56-16 52-11s-26 10-40 19ZM275 635c19 21 53 27 51 31-3 5-41 1-56-18-23-27-24-57-17-54 5 2 11 29 22 41ZM345 309l-12 10c-31 25-53 60-56 58-7-3 22-46 54-69 47-34 74-44 74-38 0 3-11 4-60 39ZM788 585l8-9c21-25 33-56 36-55 5 2-9 43-31 65-31 33-54 42-54 37 0-2 8-4 41-38ZM335 385c3 11 5 17 6 48 1 36 9 80 0 80-5 1-10-38-12-77-2-33-1-41 0-53 0-9 5-7 6 2ZM374 337c38-9 167-21 202-22 40 0 45 7-15 10-51 4-172 22-185 21-16-1-15-6-2-9ZM437 204c-27 9-33 24-35 20-4-5 11-22 34-31 16-6 39-7 34-2-5 4-17 7-33 13ZM559 188c-14 2-29 14-29 10-1-6 16-17 29-18 10 0 24 3 24 6l-24 2ZM238 334c-19 21-13 36-18 34s-10-24 8-40c14-13 28-18 32-13s-10 7-22 19Z")
+            .attr("fill", palette.highlight),
+    ]
+}
+
+fn glyph_fa(palette: &Palette) -> Vec<Element> {
+    vec![
+        Element::new("g")
+            .attr("id", "fa")
+            .child(
+                Element::new("path")
+                    .attr("d", "M513 375c-8 2-41 6-43 22-2 14 30-2 35 2 4 3-2 15-7 20-6 4-19 4-25 6-5 1-13-1-14 2-1 4 4 15 8 17 4 3 18-6 18-1 1 6-9 24-16 33-6 9-17 16-21 24-3 5-12 11-12 16-1 3 6 6 6 7 0 2 5 1 8 0 3-2 10-6 13-10 5-4 9-11 13-17 6-7 13-19 19-24 4-3 14-6 17-5 2 1-1 8-3 11l-19 22c-2 2-11 2-13 4-1 2 6 6 7 7 2 1 15-5 18-4 4 1 4 7 2 9-3 5-16 11-19 17-2 2-8 5-8 8 0 2 6 5 7 6 1 3 6 2 9 1l18-13c3-1 8-3 10-2s2 5 1 8l-8 21c-2 3-5 7-8 6-3 0-7-11-11-10-4 0-16 8-13 14 2 5 21 18 28 20 6 1 12-6 15-10 6-9 13-34 21-42 6-6 23-8 27-5s1 17-4 22c-5 4-22 1-26 4-2 2-6 3-4 6l9 8c5 2 17 1 24-1 10-4 21-21 35-21 14 1 39 20 52 22 7 1 21-6 20-11 0-4-12-13-21-17-11-5-37-7-45-12-6-5-2-17-1-22 0-3 7-5 7-8-1-4-13-10-11-17 2-6 22-16 22-20 0-2-3-6-7-7-6-2-14 4-16 2-4-3-1-11-1-15 1-5 1-14 5-14 5 0 14 9 22 12s16 5 25 6c9 0 24 1 29-2 6-4 10-15 4-18-2-1-4-6-9-7-9-2-23 4-32 2-15-4-43-16-50-21-3-2 6-5 9-7l20-8c3-2 4-6 4-9s-3-5-6-8l-10-10c-9-1-24 30-34 34-6 4-21-4-21-7 1-4 20-9 25-12 2-1 6 0 6-2 0-5-2-21-7-24s-17 2-23 5c-8 5-15 17-22 20-5 4-14 4-19 3-4 0-5-8-9-6Zm84 131c-2-1-22 12-25 16-2 1 5 2 7 3 3 1 7 9 10 6s11-23 8-25Zm-74-105c-3 2-2 7-4 10-2 5-9 15-11 21-1 6-3 16 0 17 4 2 16-7 20-8l6 1c3 2 11 8 12 11 2 2 1 5-1 7l-12 15c-1 2-5 4-5 6s5 2 7 3c2 2 5 6 4 9-1 4-10 12-13 16-2 2-6 4-5 6 2 4 13 14 18 15 6 1 13-5 15-8 1-2-6-4-4-6 6-6 38-22 44-30 4-5-7-9-9-15-1-6 1-18-1-20-3-3-12 1-14 5-2 5 1 14 1 20-1 5-1 10-4 14s-11 14-13 10c-1-1-7-3-7-6 0-7 6-17 6-24 0-9-4-22-3-27 2-4 10 0 14-2 5-2 17-8 21-12 3-2 5-8 4-10-2-2-7-4-10-3-5 2-14 12-19 12-4 0-10-8-9-11s17-4 16-7-14-10-21-11c-8-1-18 0-23 2Z")
+                    .attr("fill", palette.glyph[0]),
+            )
+            .child(
+                Element::new("path")
+                    .attr("d", "M507 369c-9 2-41 6-44 22-1 14 30-2 35 2 5 3-2 15-7 20-5 4-19 4-25 6-5 1-12-1-13 2-1 4 3 15 7 17 5 3 18-6 19-1 0 6-10 24-16 33s-18 16-22 24c-4 7-6 19-6 23 0 2 6 1 8 0l14-10 13-17c5-7 12-19 18-24 5-3 15-6 17-5s-1 8-3 11l-19 22c-3 3-8 10-6 11 3 1 15-5 19-4 3 1 3 7 1 9-2 5-15 11-19 17-3 4-2 12-1 14 1 3 6 2 9 1l18-13c3-1 9-3 10-2 2 1 2 5 1 8-1 5-5 16-8 21-2 3-5 7-8 6-3 0-6-11-10-11-4 1-16 9-14 15 3 5 21 18 28 20 6 1 12-6 15-10 6-9 13-34 21-42 7-6 24-8 28-5s1 17-4 22c-5 4-23 1-26 4-4 3 0 11 5 14 4 2 16 1 23-2 10-3 21-20 35-20 14 1 40 20 52 22 8 1 21-6 21-11-1-4-14-12-23-16-11-5-36-7-43-13-6-5-3-17-2-22 0-3 8-5 7-8-1-4-13-10-11-17 2-6 22-16 22-20s-19-1-23-5c-4-3-1-11 0-15 0-5 0-14 4-14 5 0 14 9 22 12s17 5 25 6c10 0 24 1 29-2 6-4 10-15 5-18-7-4-29-1-42-5-15-4-42-16-49-21-3-2 5-5 9-7 4-3 15-6 19-8 3-2 5-6 4-9 0-3-3-6-5-8s-5-7-7-5c-8 4-28 26-37 29-7 3-22-4-22-7 1-4 20-9 25-12 2-1 7 0 6-3 0-3-2-17-8-18-5-1-19 9-26 12-6 3-11 7-17 8-6 2-15 4-20 3-4 0-5-8-8-6Zm84 131c-3-1-23 12-26 15-2 2 5 3 7 4 3 1 7 9 10 6s12-23 9-25Zm-74-105c-4 2-3 7-5 10-2 5-9 15-10 21-2 6-4 16 0 17 3 2 15-7 19-8l6 1 13 11c1 2 0 5-1 7l-12 15c-2 2-6 4-6 6 1 2 6 2 7 3 2 2 6 6 5 9-2 4-11 12-14 16-1 2-6 4-5 6 3 4 13 14 18 15 6 1 13-5 15-8 2-2-6-4-4-6 7-6 38-23 44-30 4-5-7-9-8-15-2-6 1-18-2-20-2-3-11 1-14 5-2 5 1 14 1 20-1 5-1 10-4 14-2 4-11 14-13 10-3-3 0-20-1-30 0-9-4-22-2-27 1-4 9 0 13-2 6-2 17-8 21-12 3-2 5-8 4-10s-7-4-10-3l-18 12c-3 0-5-5-4-7 1-3 12-7 10-11s-14-10-21-11-18 0-22 2Z")
+                    .attr("fill", palette.glyph[1]),
+            )
+            .child(
+                Element::new("path")
+                    .attr("d", "M483 386c-11 5-14 9-16 8-2-2 1-11 11-15 8-3 15-6 16-3 1 2-4 7-11 10ZM555 373c-11 5-20 6-21 5-2-1 10-7 19-12 7-3 11-6 12-3 1 2-4 7-10 10ZM553 445l-6 4c-2 0-2-9 0-10 2-2 11-3 11 0l-5 6ZM454 497c-8 9-9 12-11 11s-1-8 3-13c5-5 26-28 27-25 2 2-13 22-19 27Z")
+                    .attr("fill", palette.highlight),
+            ),
+    ]
+}
+
+fn crust_defs(palette: &Palette) -> Vec<Element> {
+    vec![
+        Element::new("linearGradient")
+            .attr("id", "a")
+            .attr("x1", 0)
+            .attr("y1", 0)
+            .attr("x2", 1)
+            .attr("y2", 0)
+            .attr("gradientUnits", "userSpaceOnUse")
+            .attr("gradientTransform", "scale(778.973) rotate(68 -3 2)")
+            .child(
+                Element::new("stop")
+                    .attr("offset", 0)
+                    .attr("stop-color", palette.filling[0]),
+            )
+            .child(
+                Element::new("stop")
+                    .attr("offset", ".5")
+                    .attr("stop-color", palette.filling[1]),
+            )
+            .child(
+                Element::new("stop")
+                    .attr("offset", 1)
+                    .attr("stop-color", palette.filling[2]),
+            ),
+        Element::new("linearGradient")
+            .attr("id", "b")
+            .attr("x1", 0)
+            .attr("y1", 0)
+            .attr("x2", 1)
+            .attr("y2", 0)
+            .attr("gradientUnits", "userSpaceOnUse")
+            .attr("gradientTransform", "scale(499.109) rotate(51 -6 4)")
+            .child(
+                Element::new("stop")
+                    .attr("offset", 0)
+                    .attr("stop-color", palette.rim[0]),
+            )
+            .child(
+                Element::new("stop")
+                    .attr("offset", 1)
+                    .attr("stop-color", palette.rim[1]),
+            ),
+    ]
+}
+
+fn background_night(_: &Palette) -> Vec<Element> {
+    vec![
+        Element::new("path")
+            .attr("fill", "#101a3a")
+            .attr("d", "M0 0h1080v1080H0z"),
+        Element::new("circle")
+            .attr("cx", 900)
+            .attr("cy", 180)
+            .attr("r", 110)
+            .attr("fill", "#fff4c8")
+            .attr("opacity", ".9"),
+    ]
+}
+
+// Stroke outlines on the grid of the `fa` glyph, built from straight segments rather than set
+// in a font, so that they render the same without CJK fonts.
+const YUE: &str = "M492 372L514 372L514 512L492 512ZM492 512L514 512L488 556L462 550ZM514 372L632 372L632 392L514 392ZM610 392L632 392L632 540L610 540ZM610 540L632 540L626 556L610 562L584 556L598 546ZM514 430L610 430L610 448L514 448ZM514 480L610 480L610 498L514 498Z";
+const YUAN: &str = "M468 368L488 368L488 560L468 560ZM632 368L652 368L652 560L632 560ZM488 368L632 368L632 386L488 386ZM488 540L632 540L632 558L488 558ZM528 402L542 402L542 438L528 438ZM578 402L592 402L592 438L578 438ZM542 402L578 402L578 414L542 414ZM542 426L578 426L578 438L542 438ZM514 448L528 448L528 504L514 504ZM592 448L606 448L606 504L592 504ZM528 448L592 448L592 461L528 461ZM553 461L567 461L567 503L553 503ZM553 503L567 503L528 532L512 526ZM567 507L575 499L612 522L604 531Z";
+const FU: &str = "M494 366L508 364L518 384L504 388ZM474 394L526 394L526 408L474 408ZM512 408L528 408L482 474L468 466ZM498 436L514 436L514 558L498 558ZM514 450L522 444L538 466L528 472ZM548 368L652 368L652 382L548 382ZM564 392L578 392L578 434L564 434ZM622 392L636 392L636 434L622 434ZM578 392L622 392L622 404L578 404ZM578 422L622 422L622 434L578 434ZM552 444L568 444L568 558L552 558ZM632 444L648 444L648 558L632 558ZM568 444L632 444L632 458L568 458ZM568 492L632 492L632 504L568 504ZM568 544L632 544L632 558L568 558ZM593 458L607 458L607 544L593 544Z";
+
+/// A glyph stamped like `fa`, its shadow drawn from the same outline.
+fn stamped(id: &'static str, outline: &'static str, palette: &Palette) -> Vec<Element> {
+    let href = format!("#{}", id);
+    vec![
+        Element::new("path")
+            .attr("id", id)
+            .attr("d", outline)
+            .attr("fill-rule", "nonzero"),
+        Element::new("g")
+            .attr("id", "fa")
+            .child(
+                Element::new("use")
+                    .attr("href", &href)
+                    .attr("fill", palette.glyph[0])
+                    .attr("transform", "translate(6 6)"),
+            )
+            .child(
+                Element::new("use")
+                    .attr("href", &href)
+                    .attr("fill", palette.glyph[1]),
+            ),
+    ]
+}
+
+fn glyph_yue(palette: &Palette) -> Vec<Element> {
+    stamped("yue", YUE, palette)
+}
+
+fn glyph_yuan(palette: &Palette) -> Vec<Element> {
+    stamped("yuan", YUAN, palette)
+}
+
+fn glyph_fu(palette: &Palette) -> Vec<Element> {
+    stamped("fu", FU, palette)
+}
+
+fn overlay_none(_: &Palette) -> Vec<Element> {
+    Vec::new()
+}
+
+fn overlay_sparkles(palette: &Palette) -> Vec<Element> {
+    vec![Element::new("g")
+        .attr("fill", palette.highlight)
+        .child(
+            Element::new("circle")
+                .attr("cx", 180)
+                .attr("cy", 170)
+                .attr("r", 7),
+        )
+        .child(
+            Element::new("circle")
+                .attr("cx", 260)
+                .attr("cy", 90)
+                .attr("r", 4),
+        )
+        .child(
+            Element::new("circle")
+                .attr("cx", 960)
+                .attr("cy", 420)
+                .attr("r", 6),
+        )
+        .child(
+            Element::new("circle")
+                .attr("cx", 880)
+                .attr("cy", 980)
+                .attr("r", 5),
+        )
+        .child(
+            Element::new("circle")
+                .attr("cx", 120)
+                .attr("cy", 880)
+                .attr("r", 4),
+        )]
+}
+
+/// A selectable variant of a layer, drawn in the palette. Glyphs define the `#fa` group the
+/// glitch bands are drawn from.
+pub struct Variant {
+    pub name: &'static str,
+    pub draw: fn(&Palette) -> Vec<Element>,
+}
+
+pub const BACKGROUNDS: &[Variant] = &[
+    Variant {
+        name: "none",
+        draw: background_none,
+    },
+    Variant {
+        name: "night",
+        draw: background_night,
+    },
+];
+pub const GLYPHS: &[Variant] = &[
+    Variant {
+        name: "fa",
+        draw: glyph_fa,
+    },
+    Variant {
+        name: "yue",
+        draw: glyph_yue,
+    },
+    Variant {
+        name: "yuan",
+        draw: glyph_yuan,
+    },
+    Variant {
+        name: "fu",
+        draw: glyph_fu,
+    },
+];
+pub const OVERLAYS: &[Variant] = &[
+    Variant {
+        name: "none",
+        draw: overlay_none,
+    },
+    Variant {
+        name: "sparkles",
+        draw: overlay_sparkles,
+    },
+];
+
+fn root() -> Element {
+    Element::new("svg")
+        .attr("viewBox", "0 0 1080 1080")
+        .attr("xmlns", "http://www.w3.org/2000/svg")
+        .attr("xml:space", "preserve")
+        .attr("fill-rule", "evenodd")
+        .attr("clip-rule", "evenodd")
+        .attr("stroke-linecap", "round")
+        .attr("stroke-linejoin", "round")
+        .attr("stroke-miterlimit", "1.6")
+}
+
+fn render(elements: Vec<Element>) -> String {
+    elements.iter().map(Element::render).collect()
+}
+
+pub const VERSION: u8 = 1;
+
+/// Layer variants of a mooncake.
+pub struct Layers {
+    pub background: &'static Variant,
+    pub glyph: &'static Variant,
+    pub overlay: &'static Variant,
+    /// Rotation of the glyph and its glitch bands, in degrees.
+    pub glyph_rotation: i8,
+}
+
+pub const CLASSIC_LAYERS: Layers = Layers {
+    background: &BACKGROUNDS[0],
+    glyph: &GLYPHS[0],
+    overlay: &OVERLAYS[0],
+    glyph_rotation: 0,
+};
+
+#[derive(PartialEq)]
+pub enum LayerChoice {
+    Classic,
+    /// Picked by `seed[13]`, `seed[21]` and `seed[29]`, bytes no other feature uses.
+    Seeded,
+}
+
+pub enum BandLayout {
+    /// Band heights straight from the seed, which can add up to more than 100%. Bands below
+    /// the image are not drawn, and a filler band pads the layout when they add up to less.
+    Legacy,
+    /// Seed bytes weigh the bands, which always partition exactly 0–100%.
+    Normalised,
+}
+
+#[derive(PartialEq)]
+pub enum PaletteChoice {
+    Fixed(&'static Palette),
+    /// Picked by `seed[5]`, a byte the glitch bands don't use.
+    Seeded,
+}
+
+impl PaletteChoice {
+    pub fn pick(&self, seed: &Seed) -> &'static Palette {
+        match self {
+            PaletteChoice::Fixed(palette) => palette,
+            PaletteChoice::Seeded => palette::by_seed_byte(seed[5]),
+        }
+    }
+}
+
+/// The 2022 glitch mooncake, drawn in the style of its edition.
+pub struct Facai {
+    pub version: u8,
+    pub name: &'static str,
+    pub band_layout: BandLayout,
+}
+
+/// The original 2022 generator.
+pub const FACAI_V1: Facai = Facai {
+    version: VERSION,
+    name: "facai v1",
+    band_layout: BandLayout::Legacy,
+};
+/// Glitch bands that always fill the image.
+pub const FACAI_V2: Facai = Facai {
+    version: 2,
+    name: "facai v2",
+    band_layout: BandLayout::Normalised,
+};
+
+impl LayerChoice {
+    pub fn pick(&self, seed: &Seed) -> Layers {
+        match self {
+            LayerChoice::Classic => CLASSIC_LAYERS,
+            LayerChoice::Seeded => Layers {
+                background: &BACKGROUNDS[seed[21] as usize % BACKGROUNDS.len()],
+                glyph: &GLYPHS[seed[13] as usize % GLYPHS.len()],
+                overlay: &OVERLAYS[seed[29] as usize % OVERLAYS.len()],
+                glyph_rotation: (seed[13] >> 4) as i8 - 8,
+            },
+        }
+    }
+}
+
+impl ArtGenerator for Facai {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn svg_with(&self, seed: &Seed, style: &Style, extras: &Extras) -> String {
+        if self.version == VERSION
+            && *style == Style::CLASSIC
+            && extras.inscription.is_none()
+            && extras.evolution == 0
+        {
+            return legacy(seed);
+        }
+        new(seed, style, &self.band_layout, extras)
+    }
+
+    fn attributes(&self, seed: &Seed, style: &Style) -> Attributes {
+        let mut attributes = traits(seed, &self.band_layout).attributes();
+        if let PaletteChoice::Seeded = style.palette {
+            attributes.push(("Palette", style.palette.pick(seed).name.into()));
+        }
+        if let LayerChoice::Seeded = style.layers {
+            let layers = style.layers.pick(seed);
+            attributes.push(("Background", layers.background.name.into()));
+            attributes.push(("Glyph", layers.glyph.name.into()));
+            attributes.push(("Overlay", layers.overlay.name.into()));
+        }
+        if style.animated {
+            attributes.push(("Animation Speed", animation_speed(seed).into()));
+        }
+        attributes
+    }
+}
+
+/// Traits of a mooncake, decoded from the same seed bits the glitch bands are drawn from.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FacaiTraits {
+    /// Number of glitch bands drawn, including split bands and the filler band.
+    pub bands: u8,
+    /// Number of seed bands tall enough to be split into three.
+    pub split_bands: u8,
+    /// Hue family of the flood colour covering the largest part of the image.
+    pub hue_family: &'static str,
+    pub hue_rotation: bool,
+    /// Largest horizontal or vertical displacement of any band, in pixels.
+    pub max_offset: u8,
+}
+
+impl FacaiTraits {
+    pub fn attributes(&self) -> Attributes {
+        vec![
+            ("Glitch Bands", self.bands.into()),
+            ("Split Bands", self.split_bands.into()),
+            ("Hue Family", self.hue_family.into()),
+            ("Hue Rotation", self.hue_rotation.into()),
+            ("Max Offset", self.max_offset.into()),
+        ]
+    }
+}
+
+struct Glitch {
+    /// Seed band the glitch was drawn from, `None` for the filler band.
+    band: Option<usize>,
+    y: u8,
+    height: u8,
+    dx1: i8,
+    dy1: i8,
+    dx2: i8,
+    dy2: i8,
+    c1: u16,
+    c2: u16,
+}
+
+struct Layout {
+    glitches: Vec<Glitch>,
+    split_bands: u8,
+}
+
+pub fn new(seed: &Seed, style: &Style, band_layout: &BandLayout, extras: &Extras) -> String {
+    let layers = &style.layers.pick(seed);
+    // Gifting adds a halo, then two more glitch bands, then gilds the crust.
+    let mut glitches = layout(seed, band_layout).glitches;
+    if extras.evolution >= 2 {
+        glitches.extend(evolved_glitches(seed));
+    }
+    let gilded;
+    let palette = if extras.evolution >= 3 {
+        gilded = palette::gilded(style.palette.pick(seed));
+        &gilded
+    } else {
+        style.palette.pick(seed)
+    };
+    let filters = glitches.iter().enumerate().map(|(id, g)| {
+        let animation = match g.band {
+            Some(band) if style.animated => Some(animation(seed, band)),
+            _ => None,
+        };
+        gen_glitch(id as u8, g, palette, animation)
+    });
+    let text = if style.described {
+        let traits = traits(seed, band_layout);
+        let description = Description {
+            palette: palette.name,
+            background: layers.background.name,
+            glyph: layers.glyph.name,
+            overlay: layers.overlay.name,
+            glyph_rotation: layers.glyph_rotation,
+            inscription: extras.inscription,
+            traits: &traits,
+        };
+        describe(&description)
+    } else {
+        Vec::new()
+    };
+    let mut additions = Vec::new();
+    if extras.evolution >= 1 {
+        additions.push((Z_HALO, halo(seed)));
+    }
+    if let Some(text) = extras.inscription {
+        additions.push((Z_INSCRIPTION, inscription(text, palette)));
+    }
+    generate(
+        glitches.len() as u8,
+        filters,
+        palette,
+        layers,
+        text,
+        additions,
+    )
+}
+
+/// The art of the original 2022 generator, byte for byte as it was minted. Tokens store it in
+/// their `media`, so none of the markup may change, whitespace included.
+fn legacy(seed: &Seed) -> String {
+    let glitches = layout(seed, &BandLayout::Legacy).glitches;
+    let elements: String = (0..glitches.len())
+        .map(|i| format!("<use href=\"#fa\" filter=\"url(#g{})\"/>", i))
+        .collect();
+    let filters: String = glitches
+        .iter()
+        .enumerate()
+        .map(|(id, glitch)| legacy_glitch(id as u8, glitch))
+        .collect();
+    let lines = |draw: fn(&Palette) -> Vec<Element>| -> String {
+        draw(&palette::CLASSIC)
+            .iter()
+            .map(Element::render_lines)
+            .collect()
+    };
+    format!(
+        "{}{}{}\n{}{}{}{}\n{}{}{}",
+        r#"<svg viewBox="0 0 1080 1080" xmlns="http://www.w3.org/2000/svg" xml:space="preserve" fill-rule="evenodd" clip-rule="evenodd" stroke-linecap="round" stroke-linejoin="round" stroke-miterlimit="1.6">"#,
+        lines(background_none),
+        lines(crust),
+        elements,
+        "<defs>",
+        lines(glyph_fa),
+        lines(crust_defs),
+        filters,
+        "</defs>",
+        "</svg>"
+    )
+}
+
+fn legacy_glitch(id: u8, glitch: &Glitch) -> String {
+    let Glitch {
+        y,
+        height,
+        dx1,
+        dy1,
+        dx2,
+        dy2,
+        c1,
+        c2,
+        ..
+    } = *glitch;
+    format!(
+        "<filter id=\"g{id}\" x=\"0\" y=\"{y}%\" width=\"100%\" height=\"{height}%\">
+      <feFlood flood-color=\"hsl({c1}deg, 85%, 50%)\" result=\"f\" />
+      <feOffset in=\"SourceGraphic\" dx=\"{dx1}\" dy=\"{dy1}\" result=\"a\"/>
+      <feOffset in=\"SourceGraphic\" dx=\"{dx2}\" dy=\"{dy2}\" result=\"b\"/>
+      <feColorMatrix in=\"b\" type=\"hueRotate\" values=\"{c2}\" result=\"m\"/>
+      <feComposite in=\"f\" in2=\"a\" operator=\"in\" result=\"c\" />
+      <feMerge>
+        <feMergeNode in = \"c\" />
+        <feMergeNode in = \"m\" />
+      </feMerge>
+    </filter>"
+    )
+}
+
+/// Glitch bands a token gains by being gifted, drawn from the "evolution" seed stream. They are
+/// thin and can overlap the bands of the seed.
+fn evolved_glitches(seed: &Seed) -> Vec<Glitch> {
+    let mut bytes = seed.stream("evolution");
+    let mut next = || bytes.next().unwrap();
+    (0..2)
+        .map(|_| {
+            let height = 2 + next() % 6;
+            Glitch {
+                band: None,
+                y: next() % (100 - height),
+                height,
+                dx1: (next() & 0b00111111) as i8 - 32,
+                dy1: 0,
+                dx2: (next() & 0b00001111) as i8 - 8,
+                dy2: 0,
+                c1: u16::from_le_bytes([next(), next()]) % 360,
+                c2: 0,
+            }
+        })
+        .collect()
+}
+
+/// A ring behind the mooncake, its hue and width drawn from the "halo" seed stream.
+fn halo(seed: &Seed) -> String {
+    let mut bytes = seed.stream("halo");
+    let hue = u16::from_le_bytes([bytes.next().unwrap(), bytes.next().unwrap()]) % 360;
+    let width = 12 + bytes.next().unwrap() % 24;
+    Element::new("circle")
+        .attr("cx", 548)
+        .attr("cy", 578)
+        .attr("r", 470)
+        .attr("fill", "none")
+        .attr("stroke", format!("hsl({}deg, 90%, 70%)", hue))
+        .attr("stroke-width", width)
+        .attr("opacity", ".6")
+        .render()
+}
+
+/// `<title>` elements in every locale, then `<desc>` elements.
+fn describe(description: &Description) -> Vec<Element> {
+    let titles = facai_text::LOCALES.iter().map(|locale| {
+        Element::new("title")
+            .attr("lang", locale.lang)
+            .text((locale.title)(description))
+    });
+    let descs = facai_text::LOCALES.iter().map(|locale| {
+        Element::new("desc")
+            .attr("lang", locale.lang)
+            .text((locale.desc)(description))
+    });
+    titles.chain(descs).collect()
+}
+
+/// Keyframes of an animated band, drawn from the seed bytes 6 and 7 of the band, which the
+/// static layout doesn't use.
+struct Animation {
+    /// Duration of one loop, in centiseconds.
+    duration: u16,
+    jitter: [i8; 2],
+}
+
+fn animation(seed: &Seed, band: usize) -> Animation {
+    let speed = seed[band * 8 + 6];
+    let jitter = seed[band * 8 + 7];
+    Animation {
+        duration: 50 + (speed & 0b00001111) as u16 * 25,
+        jitter: [(jitter & 0b00001111) as i8 - 8, (jitter >> 4) as i8 - 8],
+    }
+}
+
+fn animation_speed(seed: &Seed) -> &'static str {
+    let fastest = (0..4)
+        .map(|band| animation(seed, band).duration)
+        .min()
+        .unwrap();
+    match fastest {
+        0..=99 => "fast",
+        100..=249 => "medium",
+        _ => "slow",
+    }
+}
+
+pub fn traits(seed: &Seed, band_layout: &BandLayout) -> FacaiTraits {
+    let layout = layout(seed, band_layout);
+    let mut height_by_family: Vec<(&'static str, u16)> = Vec::new();
+    let mut hue_rotation = false;
+    let mut max_offset: u8 = 0;
+    for g in &layout.glitches {
+        if g.dx1 == 0 && g.dy1 == 0 && g.dx2 == 0 && g.dy2 == 0 {
+            // The filler band is not displaced, so neither its flood nor its hue shows.
+            continue;
+        }
+        let family = hue_family(g.c1);
+        match height_by_family.iter_mut().find(|(f, _)| *f == family) {
+            Some((_, height)) => *height += g.height as u16,
+            None => height_by_family.push((family, g.height as u16)),
+        }
+        hue_rotation |= g.c2 != 0;
+        for offset in [g.dx1, g.dy1, g.dx2, g.dy2] {
+            max_offset = max_offset.max(offset.unsigned_abs());
+        }
+    }
+    let hue_family = height_by_family
+        .iter()
+        .fold(
+            None,
+            |dominant: Option<&(&'static str, u16)>, item| match dominant {
+                Some(d) if d.1 >= item.1 => Some(d),
+                _ => Some(item),
+            },
+        )
+        .map_or("none", |(family, _)| family);
+    FacaiTraits {
+        bands: layout.glitches.len() as u8,
+        split_bands: layout.split_bands,
+        hue_family,
+        hue_rotation,
+        max_offset,
+    }
+}
+
+fn layout(seed: &Seed, band_layout: &BandLayout) -> Layout {
+    let heights = match band_layout {
+        BandLayout::Legacy => [0, 1, 2, 3].map(|i| seed[i * 8] >> 2),
+        BandLayout::Normalised => normalised_heights(seed),
+    };
+    let mut sum: u8 = 0;
+    let mut glitches: Vec<Glitch> = Vec::new();
+    let mut split_bands: u8 = 0;
+    for i in 0..4 {
+        let h: u8 = heights[i];
+        let dx1: i8 = (seed[i * 8 + 1] & 0b00011111u8) as i8 - 0b00010000;
+        let dy1: i8 = (seed[i * 8 + 2] & 0b00000111u8) as i8 - 0b00000100;
+        // let dx1: i8 = seed[i * 8 + 1] as i8 >> 2;
+        // let dy1: i8 = seed[i * 8 + 2] as i8 >> 4;
+        let dx2: i8 = (seed[i * 8 + 1] & 0b00001111u8) as i8;
+        let dy2: i8 = (seed[i * 8 + 2] & 0b00000011u8) as i8;
+        let c1: u16 = fold_cubic(seed[i * 8 + 3] >> 2);
+        let c2: u16 = if seed[i * 8 + 4] & 0b00001111 == 0b0001111 {
+            seed[i * 8 + 4].into()
+        } else {
+            0
+        };
+        if h > 30 {
+            let (h2, y2) = match band_layout {
+                BandLayout::Legacy => {
+                    let h2: u8 = seed[i * 8 + 4] & 3;
+                    (h2, seed[i * 8 + 4] % (h - h2))
+                }
+                // None of the three parts is empty.
+                BandLayout::Normalised => {
+                    let h2: u8 = 1 + (seed[i * 8 + 4] & 3);
+                    (h2, 1 + (seed[i * 8 + 4] >> 2) % (h - h2 - 1))
+                }
+            };
+
+            glitches.push(Glitch {
+                band: Some(i),
+                y: sum,
+                height: y2,
+                dx1,
+                dy1,
+                dx2,
+                dy2,
+                c1,
+                c2,
+            });
+            glitches.push(Glitch {
+                band: Some(i),
+                y: sum + y2,
+                height: h2,
+                dx1: if dx1 > 0 {
+                    (dx1 << 2) + 10
+                } else {
+                    (dx1 << 2) - 10
+                },
+                dy1,
+                dx2: dx2 << 1,
+                dy2,
+                c1,
+                c2,
+            });
+            glitches.push(Glitch {
+                band: Some(i),
+                y: sum + y2 + h2,
+                height: h - y2 - h2,
+                dx1,
+                dy1,
+                dx2,
+                dy2,
+                c1,
+                c2,
+            });
+            split_bands += 1;
+        } else if h > 0 || matches!(band_layout, BandLayout::Legacy) {
+            glitches.push(Glitch {
+                band: Some(i),
+                y: sum,
+                height: h,
+                dx1,
+                dy1,
+                dx2,
+                dy2,
+                c1,
+                c2,
+            });
+        }
+        sum += h;
+    }
+    if sum < 100 {
+        glitches.push(Glitch {
+            band: None,
+            y: sum,
+            height: 100 - sum,
+            dx1: 0,
+            dy1: 0,
+            dx2: 0,
+            dy2: 0,
+            c1: 0,
+            c2: 0,
+        });
+    }
+    Layout {
+        glitches,
+        split_bands,
+    }
+}
+
+/// Heights of the four bands in percent, adding up to exactly 100, proportional to weights
+/// from the seed. Rounding is by largest remainder, ties going to the upper band.
+fn normalised_heights(seed: &Seed) -> [u8; 4] {
+    let weights = [0, 1, 2, 3].map(|i| (seed[i * 8] >> 2) as u16 + 1);
+    let total: u16 = weights.iter().sum();
+    let mut heights = weights.map(|w| (w * 100 / total) as u8);
+    let remainders = weights.map(|w| w * 100 % total);
+    let mut order = [0, 1, 2, 3];
+    order.sort_by_key(|&i| std::cmp::Reverse(remainders[i]));
+    let missing = 100 - heights.iter().sum::<u8>();
+    for &i in &order[..missing as usize] {
+        heights[i] += 1;
+    }
+    heights
+}
+
+fn hue_family(hue: u16) -> &'static str {
+    match hue % 360 {
+        0..=14 | 345..=359 => "red",
+        15..=44 => "orange",
+        45..=74 => "yellow",
+        75..=164 => "green",
+        165..=194 => "cyan",
+        195..=254 => "blue",
+        255..=314 => "purple",
+        _ => "pink",
+    }
+}
+
+fn fold_cubic(v: u8) -> u16 {
+    let a = v & 0b11110000 >> 4;
+    let b = v & 0b00001111;
+    let c: u16 = (a & b).into();
+    c * c * c
+}
+
+fn generate(
+    n: u8,
+    filters: impl Iterator<Item = Element>,
+    palette: &Palette,
+    layers: &Layers,
+    text: Vec<Element>,
+    additions: Vec<(u8, String)>,
+) -> String {
+    let elements: String = (0..n)
+        .map(|i| {
+            Element::new("use")
+                .attr("href", "#fa")
+                .attr("filter", format!("url(#g{})", i))
+                .render()
+        })
+        .collect();
+    let glyph_transform = match layers.glyph_rotation {
+        0 => None,
+        rotation => Some(format!("rotate({} 560 465)", rotation)),
+    };
+    let root = if text.is_empty() {
+        root()
+    } else {
+        root().attr("role", "img")
+    };
+    let mut composition = Composition::new(root);
+    for element in text {
+        composition.head(element);
+    }
+    composition.layer(
+        Z_BACKGROUND,
+        render((layers.background.draw)(palette)),
+        None,
+    );
+    composition.layer(Z_CRUST, render(crust(palette)), None);
+    composition.layer(Z_GLITCH, elements, glyph_transform);
+    for (z, markup) in additions {
+        composition.layer(z, markup, None);
+    }
+    composition.layer(Z_OVERLAY, render((layers.overlay.draw)(palette)), None);
+    composition.def(render((layers.glyph.draw)(palette)));
+    composition.def(render(crust_defs(palette)));
+    for filter in filters {
+        composition.def(filter.render());
+    }
+    composition.render()
+}
+
+/// The owner's inscription, stamped below the glyph with a shadow like the glyph. The font
+/// shrinks so that the widest inscription still fits on the face.
+fn inscription(text: &str, palette: &Palette) -> String {
+    // In tenths of an em: ideographs are square, latin letters about 0.6em wide.
+    let width: u32 = text
+        .chars()
+        .map(|c| if c.is_ascii() { 6 } else { 10 })
+        .sum();
+    let size = (3200 / width.max(1)).min(72);
+    [(566, 676, palette.glyph[0]), (563, 673, palette.glyph[1])]
+        .iter()
+        .map(|(x, y, fill)| {
+            Element::new("text")
+                .attr("x", x)
+                .attr("y", y)
+                .attr("font-family", "serif")
+                .attr("font-size", size)
+                .attr("font-weight", "bold")
+                .attr("text-anchor", "middle")
+                .attr("fill", fill)
+                .text(text)
+                .render()
+        })
+        .collect()
+}
+
+fn gen_glitch(id: u8, glitch: &Glitch, palette: &Palette, animation: Option<Animation>) -> Element {
+    let Glitch {
+        y,
+        height,
+        dx1,
+        dy1,
+        dx2,
+        dy2,
+        c1,
+        c2,
+        ..
+    } = *glitch;
+    let mut offset_a = Element::new("feOffset")
+        .attr("in", "SourceGraphic")
+        .attr("dx", dx1)
+        .attr("dy", dy1)
+        .attr("result", "a");
+    let mut offset_b = Element::new("feOffset")
+        .attr("in", "SourceGraphic")
+        .attr("dx", dx2)
+        .attr("dy", dy2)
+        .attr("result", "b");
+    let mut hue = Element::new("feColorMatrix")
+        .attr("in", "b")
+        .attr("type", "hueRotate")
+        .attr("values", c2)
+        .attr("result", "m");
+    if let Some(Animation {
+        duration,
+        jitter: [j1, j2],
+    }) = animation
+    {
+        let dur = format!("{}.{:02}s", duration / 100, duration % 100);
+        offset_a = offset_a.child(gen_animate("dx", [dx1, dx1 + j1, dx1 + j2], &dur));
+        offset_b = offset_b.child(gen_animate("dx", [dx2, dx2 - j1, dx2 - j2], &dur));
+        hue = hue.child(gen_animate("values", [c2, c2 + 120, c2 + 240], &dur));
+    }
+    Element::new("filter")
+        .attr("id", format!("g{}", id))
+        .attr("x", 0)
+        .attr("y", format!("{}%", y))
+        .attr("width", "100%")
+        .attr("height", format!("{}%", height))
+        .child(
+            Element::new("feFlood")
+                .attr(
+                    "flood-color",
+                    format!(
+                        "hsl({}deg, {}%, {}%)",
+                        c1, palette.glitch_saturation, palette.glitch_lightness
+                    ),
+                )
+                .attr("result", "f"),
+        )
+        .child(offset_a)
+        .child(offset_b)
+        .child(hue)
+        .child(
+            Element::new("feComposite")
+                .attr("in", "f")
+                .attr("in2", "a")
+                .attr("operator", "in")
+                .attr("result", "c"),
+        )
+        .child(
+            Element::new("feMerge")
+                .child(Element::new("feMergeNode").attr("in", "c"))
+                .child(Element::new("feMergeNode").attr("in", "m")),
+        )
+}
+
+/// Discrete `<animate>` looping through `values`.
+fn gen_animate<T: std::fmt::Display>(
+    attribute: &'static str,
+    values: [T; 3],
+    dur: &str,
+) -> Element {
+    let [v0, v1, v2] = values;
+    Element::new("animate")
+        .attr("attributeName", attribute)
+        .attr("values", format!("{};{};{};{}", v0, v1, v2, v0))
+        .attr("dur", dur)
+        .attr("calcMode", "discrete")
+        .attr("repeatCount", "indefinite")
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::facai_gen::{
+        layout, new, normalised_heights, traits, BandLayout, FacaiTraits, LayerChoice,
+        PaletteChoice, FACAI_V1, FACAI_V2,
+    };
+    use crate::generator::{ArtGenerator, Extras, Seed, Style};
+    use crate::palette;
+
+    const SEEDED: Style = Style {
+        palette: PaletteChoice::Seeded,
+        ..Style::CLASSIC
+    };
+    const LAYERED: Style = Style {
+        layers: LayerChoice::Seeded,
+        ..SEEDED
+    };
+
+    /// Facai v1 drawn by `new` rather than as minted, as it is once extras are added.
+    fn composed(seed: &Seed, extras: &Extras) -> String {
+        new(seed, &Style::CLASSIC, &BandLayout::Legacy, extras)
+    }
+
+    #[test]
+    fn test_traits() {
+        let seed = Seed::new([0u8; 32]);
+        assert_eq!(
+            traits(&seed, &BandLayout::Legacy),
+            FacaiTraits {
+                bands: 5,
+                split_bands: 0,
+                hue_family: "red",
+                hue_rotation: false,
+                max_offset: 16,
+            }
+        );
+
+        let mut seed = Seed::new([0u8; 32]);
+        seed[0] = 200; // 50% band, split into three
+        seed[3] = 0b00010100; // hue 125
+        seed[4] = 0b00011111; // hue rotation 31
+        let t = traits(&seed, &BandLayout::Legacy);
+        assert_eq!(t.split_bands, 1);
+        assert_eq!(t.bands, 7);
+        assert_eq!(t.hue_family, "green");
+        assert!(t.hue_rotation);
+        assert_eq!(t.max_offset, 74);
+        assert_eq!(
+            new(
+                &seed,
+                &Style::CLASSIC,
+                &BandLayout::Legacy,
+                &Extras::default()
+            )
+            .matches("<use ")
+            .count(),
+            t.bands as usize
+        );
+    }
+
+    #[test]
+    fn test_palettes() {
+        let mut seed = Seed::new([0u8; 32]);
+        let classic = FACAI_V1.svg(&seed, &Style::CLASSIC);
+        assert!(classic.contains("#7f3a01"));
+        assert!(classic.contains("hsl(0deg, 85%, 50%)"));
+        assert_eq!(
+            FACAI_V1.svg(&seed, &SEEDED),
+            composed(&seed, &Extras::default())
+        );
+        assert_eq!(FACAI_V1.attributes(&seed, &Style::CLASSIC).len(), 5);
+
+        seed[5] = 200;
+        let matcha = FACAI_V1.svg(&seed, &SEEDED);
+        assert!(matcha.contains("#3f5a1e"));
+        assert!(!matcha.contains("#7f3a01"));
+        assert!(matcha.contains("hsl(0deg, 60%, 45%)"));
+        assert_eq!(
+            FACAI_V1.attributes(&seed, &SEEDED).last(),
+            Some(&("Palette", "matcha".into()))
+        );
+        assert_eq!(FACAI_V1.svg(&seed, &Style::CLASSIC), classic);
+
+        let lotus = Style {
+            palette: PaletteChoice::Fixed(&palette::LOTUS),
+            ..Style::CLASSIC
+        };
+        assert!(FACAI_V1.svg(&seed, &lotus).contains(palette::LOTUS.crust));
+        assert_eq!(FACAI_V1.attributes(&seed, &lotus).len(), 5);
+    }
+
+    #[test]
+    fn test_animated() {
+        let mut seed = Seed::new([0u8; 32]);
+        assert!(!FACAI_V1.svg(&seed, &Style::CLASSIC).contains("<animate"));
+
+        seed[6] = 0b00000010; // band 0 loops every 1s
+        seed[7] = 0b10010111; // jitter -1, +1
+        let animated = Style {
+            animated: true,
+            ..SEEDED
+        };
+        let svg = FACAI_V1.svg(&seed, &animated);
+        // 4 bands with 3 animated primitives each, the filler band stays still.
+        assert_eq!(svg.matches("<animate ").count(), 12);
+        assert!(svg.contains(
+            r#"<animate attributeName="dx" values="-16;-17;-15;-16" dur="1.00s" calcMode="discrete" repeatCount="indefinite"/></feOffset>"#
+        ));
+        assert!(svg.contains(r#"values="0;120;240;0" dur="1.00s""#));
+        assert_eq!(
+            FACAI_V1.attributes(&seed, &animated).last(),
+            Some(&("Animation Speed", "fast".into()))
+        );
+        assert_eq!(FACAI_V1.svg(&seed, &animated), svg);
+    }
+
+    #[test]
+    fn test_layers() {
+        let mut seed = Seed::new([0u8; 32]);
+        seed[13] = 0x82; // glyph yuan, no rotation
+        seed[21] = 1; // night
+        let svg = FACAI_V1.svg(&seed, &LAYERED);
+        assert!(svg.contains("<path id=\"yuan\" d=\"M468 368L488 368L488 560L468 560Z"));
+        assert_eq!(svg.matches("M468 368L488 368").count(), 1);
+        assert!(!svg.contains("<text"));
+        assert!(svg.contains("#101a3a"));
+        assert!(!svg.contains("<g transform="));
+        assert!(!svg.contains("<circle cx=\"180\""));
+        let attributes = FACAI_V1.attributes(&seed, &LAYERED);
+        assert!(attributes.contains(&("Glyph", "yuan".into())));
+        assert!(attributes.contains(&("Background", "night".into())));
+        assert!(attributes.contains(&("Overlay", "none".into())));
+
+        seed[13] = 0x50; // glyph fa, rotated by -3 degrees
+        seed[29] = 1; // sparkles
+        let svg = FACAI_V1.svg(&seed, &LAYERED);
+        assert!(svg.contains("<g transform=\"rotate(-3 560 465)\"><use href=\"#fa\""));
+        assert!(svg.contains("<circle cx=\"180\""));
+    }
+
+    #[test]
+    fn test_described() {
+        let mut seed = Seed::new([0u8; 32]);
+        seed[13] = 0x82; // glyph yuan, no rotation
+        seed[21] = 1; // night
+        let described = Style {
+            described: true,
+            ..LAYERED
+        };
+        let svg = FACAI_V2.svg(&seed, &described);
+        assert!(svg.starts_with(
+            r#"<svg viewBox="0 0 1080 1080" xmlns="http://www.w3.org/2000/svg" xml:space="preserve" fill-rule="evenodd" clip-rule="evenodd" stroke-linecap="round" stroke-linejoin="round" stroke-miterlimit="1.6" role="img"><title lang="en">Mooncake 圆 (reunion)</title><title lang="zh-Hans">月饼「圆」</title><desc lang="en">A classic mooncake stamped with 圆 on a night sky with a full moon. Glitch: 4 bands, 0 split in three, offsets up to 16 px, red flood, no hue rotation.</desc><desc lang="zh-Hans">"#
+        ));
+        assert_eq!(FACAI_V2.svg(&seed, &described), svg);
+        assert_eq!(
+            FACAI_V2.attributes(&seed, &described),
+            FACAI_V2.attributes(&seed, &LAYERED)
+        );
+        // The same art, with text.
+        let (head, art) = svg.split_at(svg.find("</desc><path").unwrap() + 7);
+        assert!(head.ends_with("</desc>"));
+        assert!(FACAI_V2.svg(&seed, &LAYERED).ends_with(art));
+        assert!(!FACAI_V2.svg(&seed, &LAYERED).contains("<title"));
+    }
+
+    #[test]
+    fn test_inscription() {
+        let seed = Seed::new([0u8; 32]);
+        let extras = Extras {
+            inscription: Some("中秋快乐"),
+            ..Default::default()
+        };
+        let svg = FACAI_V1.svg_with(&seed, &Style::CLASSIC, &extras);
+        assert!(svg.contains(r##"<text x="563" y="673" font-family="serif" font-size="72" font-weight="bold" text-anchor="middle" fill="#b14d04">中秋快乐</text>"##));
+        // Drawn over the glitch bands, under the overlay.
+        assert!(svg.find("url(#g4)").unwrap() < svg.find("中秋快乐").unwrap());
+        assert_eq!(
+            svg.replace(&super::inscription("中秋快乐", &palette::CLASSIC), ""),
+            composed(&seed, &Extras::default())
+        );
+        let svg = FACAI_V1.svg_with(
+            &seed,
+            &Style::CLASSIC,
+            &Extras {
+                inscription: Some("Mei Ling"),
+                ..Default::default()
+            },
+        );
+        assert!(svg.contains(r#"font-size="66""#));
+
+        let described = FACAI_V1.svg_with(
+            &seed,
+            &Style {
+                described: true,
+                ..Style::CLASSIC
+            },
+            &extras,
+        );
+        assert!(described.contains("inscribed 中秋快乐"));
+        assert!(described.contains("题字「中秋快乐」"));
+    }
+
+    #[test]
+    fn test_inscription_fits() {
+        // Lowest point of any glyph and its shadow at any tilt, and the face's inner edge at a
+        // few heights below it, both measured from the paths in this file.
+        const GLYPH_BOTTOM: f64 = 598.0;
+        const FACE: [(f64, f64, f64); 3] = [
+            (690.0, 315.0, 802.0),
+            (700.0, 355.0, 786.0),
+            (710.0, 358.0, 758.0),
+        ];
+        let seed = Seed::new([0u8; 32]);
+        // Every mix of ideographs and letters at the widest an inscription may be.
+        for ideographs in 0..=crate::inscriptions::MAX_WIDTH / 2 {
+            let text = "月".repeat(ideographs)
+                + &"M".repeat(crate::inscriptions::MAX_WIDTH - 2 * ideographs);
+            crate::inscriptions::assert_valid(&text);
+            let svg = FACAI_V1.svg_with(
+                &seed,
+                &Style::CLASSIC,
+                &Extras {
+                    inscription: Some(&text),
+                    ..Default::default()
+                },
+            );
+            let number = |tag: &str, name: &str| -> f64 {
+                let start = tag.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+                tag[start..].split('"').next().unwrap().parse().unwrap()
+            };
+            for tag in svg.split("<text").skip(1) {
+                let (x, y, size) = (number(tag, "x"), number(tag, "y"), number(tag, "font-size"));
+                // Serif ascends about 0.9em and descends 0.2em; letters are 0.6em wide.
+                let width = text
+                    .chars()
+                    .map(|c| if c.is_ascii() { 0.6 } else { 1.0 })
+                    .sum::<f64>()
+                    * size;
+                let (top, bottom) = (y - 0.9 * size, y + 0.2 * size);
+                // Still readable when the mooncake is shown as a thumbnail.
+                assert!(size >= 64.0, "{:?} is shrunk to {}", text, size);
+                assert!(top > GLYPH_BOTTOM, "{:?} overlaps the glyph", text);
+                let &(_, left, right) = FACE
+                    .iter()
+                    .find(|(row, _, _)| *row >= bottom)
+                    .unwrap_or_else(|| panic!("{:?} runs off the bottom", text));
+                assert!(
+                    left < x - width / 2.0 && x + width / 2.0 < right,
+                    "{:?} runs off the face",
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_evolution() {
+        let seed = Seed::new([0u8; 32]);
+        let evolved = |evolution| {
+            FACAI_V1.svg_with(
+                &seed,
+                &Style::CLASSIC,
+                &Extras {
+                    evolution,
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(evolved(0), FACAI_V1.svg(&seed, &Style::CLASSIC));
+
+        let halo = evolved(1);
+        assert!(halo.contains(r#"<circle cx="548" cy="578" r="470" fill="none""#));
+        assert!(halo.find("<circle").unwrap() < halo.find("#7f3a01").unwrap());
+        assert_eq!(halo.matches("<use ").count(), 5);
+
+        let bands = evolved(2);
+        assert_eq!(bands.matches("<use ").count(), 7);
+        assert!(bands.contains(r#"<filter id="g6""#));
+        assert!(bands.contains("#7f3a01"));
+
+        let gilded = evolved(3);
+        assert_eq!(gilded.matches("<use ").count(), 7);
+        assert!(!gilded.contains("#7f3a01"));
+        assert!(gilded.contains("#a8740c"));
+        assert_eq!(evolved(3), gilded);
+
+        let mut other = seed.clone();
+        other[31] = 1;
+        assert_ne!(
+            FACAI_V1.svg_with(
+                &other,
+                &Style::CLASSIC,
+                &Extras {
+                    evolution: 2,
+                    ..Default::default()
+                }
+            ),
+            bands
+        );
+    }
+
+    #[test]
+    fn test_normalised_layout() {
+        assert_eq!(normalised_heights(&Seed::new([0u8; 32])), [25, 25, 25, 25]);
+        let mut seed = Seed::new([0u8; 32]);
+        seed[0] = 0xff; // weights 64, 1, 1, 1
+        assert_eq!(normalised_heights(&seed), [96, 2, 1, 1]);
+
+        let mut state: u64 = 0x9e3779b97f4a7c15;
+        for _ in 0..1000 {
+            for b in seed.iter_mut() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                *b = state as u8;
+            }
+            let layout = layout(&seed, &BandLayout::Normalised);
+            let mut bottom = 0;
+            for glitch in &layout.glitches {
+                assert_eq!(glitch.y, bottom);
+                assert!(glitch.height > 0);
+                assert!(glitch.band.is_some());
+                bottom += glitch.height;
+            }
+            assert_eq!(bottom, 100);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Localised `<title>` and `<desc>` text of the facai art, for screen readers and wallets that
+//! show the text alternative of an image.
+use crate::facai_gen::FacaiTraits;
+
+/// What a mooncake shows, by the names of its palette, layer variants and traits.
+pub struct Description<'a> {
+    pub palette: &'static str,
+    pub background: &'static str,
+    pub glyph: &'static str,
+    pub overlay: &'static str,
+    /// Clockwise rotation of the glyph, in degrees.
+    pub glyph_rotation: i8,
+    pub inscription: Option<&'a str>,
+    pub traits: &'a FacaiTraits,
+}
+
+pub struct Locale {
+    /// BCP 47 language tag, written to the `lang` attribute of the text.
+    pub lang: &'static str,
+    pub title: fn(&Description) -> String,
+    pub desc: fn(&Description) -> String,
+}
+
+/// Every language the art is described in. SVG 2 viewers show the text matching the reader's
+/// language, older ones the first.
+pub const LOCALES: &[Locale] = &[
+    Locale {
+        lang: "en",
+        title: title_en,
+        desc: desc_en,
+    },
+    Locale {
+        lang: "zh-Hans",
+        title: title_zh,
+        desc: desc_zh,
+    },
+];
+
+/// Character of a glyph variant. Names of variants added later are used as is.
+fn glyph_char(glyph: &str) -> &str {
+    match glyph {
+        "fa" => "发",
+        "yue" => "月",
+        "yuan" => "圆",
+        "fu" => "福",
+        _ => glyph,
+    }
+}
+
+fn title_en(d: &Description) -> String {
+    let meaning = match d.glyph {
+        "fa" => "fortune",
+        "yue" => "moon",
+        "yuan" => "reunion",
+        "fu" => "blessing",
+        other => other,
+    };
+    format!("Mooncake {} ({})", glyph_char(d.glyph), meaning)
+}
+
+fn desc_en(d: &Description) -> String {
+    let rotation = match d.glyph_rotation {
+        0 => String::new(),
+        r if r > 0 => format!(" turned {}° clockwise", r),
+        r => format!(" turned {}° anticlockwise", r.unsigned_abs()),
+    };
+    let background = match d.background {
+        "none" => "a transparent background",
+        "night" => "a night sky with a full moon",
+        other => other,
+    };
+    let overlay = match d.overlay {
+        "none" => String::new(),
+        other => format!(", with {}", other),
+    };
+    let inscription = match d.inscription {
+        Some(text) => format!(", inscribed {}", text),
+        None => String::new(),
+    };
+    let t = d.traits;
+    let flood = match t.hue_family {
+        "none" => "no flood".to_string(),
+        family => format!("{} flood", family),
+    };
+    format!(
+        "A {} mooncake stamped with {}{}{} on {}{}. Glitch: {} bands, {} split in three, offsets up to {} px, {}, {} hue rotation.",
+        d.palette,
+        glyph_char(d.glyph),
+        rotation,
+        inscription,
+        background,
+        overlay,
+        t.bands,
+        t.split_bands,
+        t.max_offset,
+        flood,
+        if t.hue_rotation { "with" } else { "no" }
+    )
+}
+
+fn title_zh(d: &Description) -> String {
+    format!("月饼「{}」", glyph_char(d.glyph))
+}
+
+fn desc_zh(d: &Description) -> String {
+    let palette = match d.palette {
+        "classic" => "经典",
+        "lotus" => "莲蓉",
+        "matcha" => "抹茶",
+        "snow-skin" => "冰皮",
+        other => other,
+    };
+    let rotation = match d.glyph_rotation {
+        0 => String::new(),
+        r if r > 0 => format!("，顺时针旋转{}°", r),
+        r => format!("，逆时针旋转{}°", r.unsigned_abs()),
+    };
+    let background = match d.background {
+        "none" => "透明背景",
+        "night" => "满月夜空背景",
+        other => other,
+    };
+    let overlay = match d.overlay {
+        "none" => "",
+        "sparkles" => "，点缀星光",
+        other => other,
+    };
+    let inscription = match d.inscription {
+        Some(text) => format!("，题字「{}」", text),
+        None => String::new(),
+    };
+    let t = d.traits;
+    let flood = match t.hue_family {
+        "red" => "红色",
+        "orange" => "橙色",
+        "yellow" => "黄色",
+        "green" => "绿色",
+        "cyan" => "青色",
+        "blue" => "蓝色",
+        "purple" => "紫色",
+        "pink" => "粉色",
+        "none" => "无",
+        other => other,
+    };
+    format!(
+        "{}月饼，印有「{}」{}{}，{}{}。故障效果：{}条色带，其中{}条三分，最大偏移{}像素，{}填充，{}色相旋转。",
+        palette,
+        glyph_char(d.glyph),
+        rotation,
+        inscription,
+        background,
+        overlay,
+        t.bands,
+        t.split_bands,
+        t.max_offset,
+        flood,
+        if t.hue_rotation { "有" } else { "无" }
+    )
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let traits = FacaiTraits {
+            bands: 5,
+            split_bands: 1,
+            hue_family: "green",
+            hue_rotation: true,
+            max_offset: 74,
+        };
+        let d = Description {
+            palette: "snow-skin",
+            background: "night",
+            glyph: "yuan",
+            overlay: "sparkles",
+            glyph_rotation: -3,
+            inscription: None,
+            traits: &traits,
+        };
+        assert_eq!(title_en(&d), "Mooncake 圆 (reunion)");
+        assert_eq!(
+            desc_en(&d),
+            "A snow-skin mooncake stamped with 圆 turned 3° anticlockwise on a night sky with a full moon, with sparkles. Glitch: 5 bands, 1 split in three, offsets up to 74 px, green flood, with hue rotation."
+        );
+        assert_eq!(title_zh(&d), "月饼「圆」");
+        assert_eq!(
+            desc_zh(&d),
+            "冰皮月饼，印有「圆」，逆时针旋转3°，满月夜空背景，点缀星光。故障效果：5条色带，其中1条三分，最大偏移74像素，绿色填充，有色相旋转。"
+        );
+        let d = Description {
+            inscription: Some("Mei"),
+            ..d
+        };
+        assert!(desc_en(&d).starts_with("A snow-skin mooncake stamped with 圆 turned 3° anticlockwise, inscribed Mei on a night sky"));
+        assert!(desc_zh(&d)
+            .starts_with("冰皮月饼，印有「圆」，逆时针旋转3°，题字「Mei」，满月夜空背景"));
+    }
+}
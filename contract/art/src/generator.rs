@@ -0,0 +1,397 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::ops::{Deref, DerefMut};
+
+use crate::facai_gen::{LayerChoice, PaletteChoice};
+use crate::palette;
+
+/// `(trait_type, value)` pairs describing a piece of art, as used by marketplaces and rarity.
+pub type Attributes = Vec<(&'static str, Value)>;
+
+/// The 32 random bytes a token is minted with. Generators read them directly and draw anything
+/// beyond them from `stream`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Seed([u8; Seed::LEN]);
+
+impl Seed {
+    pub const LEN: usize = 32;
+
+    pub fn new(bytes: [u8; Seed::LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// `None` unless `bytes` is exactly `Seed::LEN` long.
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
+    /// Endless bytes expanded from the seed with sha256 in counter mode. Each feature passes its
+    /// own `domain`, so features never draw the same bytes.
+    pub fn stream(&self, domain: &str) -> SeedStream {
+        SeedStream {
+            prefix: [&self.0[..], domain.as_bytes()].concat(),
+            counter: 0,
+            block: [0; 32],
+            position: 32,
+        }
+    }
+}
+
+impl Deref for Seed {
+    type Target = [u8; Seed::LEN];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Seed {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Block `n` is `sha256(seed || domain || n as u64 LE)`. Uses a plain Rust sha256 rather than
+/// `env::sha256`, so previews outside the contract render the same art.
+pub struct SeedStream {
+    prefix: Vec<u8>,
+    counter: u64,
+    block: [u8; 32],
+    position: usize,
+}
+
+impl Iterator for SeedStream {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.position == self.block.len() {
+            let mut hasher = Sha256::new();
+            hasher.update(&self.prefix);
+            hasher.update(self.counter.to_le_bytes());
+            self.block = hasher.finalize().into();
+            self.counter += 1;
+            self.position = 0;
+        }
+        self.position += 1;
+        Some(self.block[self.position - 1])
+    }
+}
+
+/// Art added to a token after it is minted, drawn on top of what its seed renders.
+#[derive(Default)]
+pub struct Extras<'a> {
+    /// Text the owner stamped on the mooncake, see `inscriptions::assert_valid`.
+    pub inscription: Option<&'a str>,
+    /// Number of gift count thresholds the token passed, see `gifts::THRESHOLDS`. Every one
+    /// adds a feature to the art.
+    pub evolution: u8,
+}
+
+/// How an edition draws its tokens, on top of what each seed decides. Every generator draws
+/// every style.
+#[derive(PartialEq)]
+pub struct Style {
+    pub palette: PaletteChoice,
+    pub layers: LayerChoice,
+    /// Animate the glitch offsets and hue rotation with SMIL `<animate>` elements.
+    pub animated: bool,
+    /// Describe the art in `<title>` and `<desc>` elements, in every `facai_text::LOCALES`.
+    pub described: bool,
+}
+
+impl Style {
+    /// Style of the 2022 edition, which facai v1 draws byte for byte as it was minted.
+    pub const CLASSIC: Style = Style {
+        palette: PaletteChoice::Fixed(&palette::CLASSIC),
+        layers: LayerChoice::Classic,
+        animated: false,
+        described: false,
+    };
+}
+
+/// Turns a seed into mooncake art. Each generator has a version, which is stored with every
+/// token seed, so a token keeps rendering the same way when new designs are added.
+pub trait ArtGenerator: Sync {
+    fn version(&self) -> u8;
+
+    fn name(&self) -> &'static str;
+
+    /// Art of a freshly minted token.
+    fn svg(&self, seed: &Seed, style: &Style) -> String {
+        self.svg_with(seed, style, &Extras::default())
+    }
+
+    fn svg_with(&self, seed: &Seed, style: &Style, extras: &Extras) -> String;
+
+    fn attributes(&self, seed: &Seed, style: &Style) -> Attributes;
+}
+
+const GENERATORS: &[&dyn ArtGenerator] =
+    &[&crate::facai_gen::FACAI_V1, &crate::facai_gen::FACAI_V2];
+
+pub fn get(version: u8) -> Option<&'static dyn ArtGenerator> {
+    GENERATORS
+        .iter()
+        .find(|generator| generator.version() == version)
+        .copied()
+}
+
+/// Generator of an already minted token, which must exist.
+pub fn by_version(version: u8) -> &'static dyn ArtGenerator {
+    get(version).unwrap_or_else(|| panic!("Unknown generator version {}", version))
+}
+
+#[derive(Serialize)]
+struct Attribute<'a> {
+    trait_type: &'a str,
+    value: &'a Value,
+}
+
+/// OpenSea-style `{"attributes": [...]}` document, as stored in `TokenMetadata.extra`. Baked
+/// tokens store their parents next to it.
+pub fn attributes_document(attributes: &Attributes) -> Value {
+    let attributes: Vec<Attribute> = attributes
+        .iter()
+        .map(|(trait_type, value)| Attribute { trait_type, value })
+        .collect();
+    json!({ "attributes": attributes })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let generator = get(1).unwrap();
+        assert_eq!(generator.name(), "facai v1");
+        assert_eq!(get(2).unwrap().name(), "facai v2");
+        assert!(get(0).is_none());
+    }
+
+    #[test]
+    fn test_attributes_document() {
+        let attributes: Attributes = vec![("Glitch Bands", 5.into()), ("Hue Family", "red".into())];
+        assert_eq!(
+            attributes_document(&attributes).to_string(),
+            r#"{"attributes":[{"trait_type":"Glitch Bands","value":5},{"trait_type":"Hue Family","value":"red"}]}"#
+        );
+    }
+
+    /// Fixed seeds rendered in every edition into `tests/golden`.
+    fn golden_seeds() -> Vec<Seed> {
+        let mut seeds = vec![Seed::new([0u8; 32]), Seed::new([0xffu8; 32])];
+        seeds.extend(random_seeds(0x6d6f6f6e63616b65).take(2));
+        seeds
+    }
+
+    fn random_seeds(mut state: u64) -> impl Iterator<Item = Seed> {
+        std::iter::repeat_with(move || {
+            Seed::new([0u8; 32].map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            }))
+        })
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Minted art must never change. Run with `UPDATE_GOLDEN=1` to write the snapshots of a new
+    /// edition, and commit them. The 2022 snapshots were rendered by the 2022 contract itself.
+    #[test]
+    fn test_golden() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+        for edition in crate::edition::EDITIONS {
+            for seed in golden_seeds() {
+                let path = dir.join(format!("{}-{}.svg", edition.id, &hex(&*seed)[..16]));
+                let svg = by_version(edition.generator).svg(&seed, &edition.style);
+                if update && !path.exists() {
+                    std::fs::create_dir_all(&dir).unwrap();
+                    std::fs::write(&path, &svg).unwrap();
+                }
+                let golden = std::fs::read_to_string(&path)
+                    .unwrap_or_else(|_| panic!("Missing snapshot {}", path.display()));
+                assert!(svg == golden, "{} changed", path.display());
+            }
+        }
+    }
+
+    struct Tag<'a> {
+        name: &'a str,
+        attributes: Vec<(&'a str, &'a str)>,
+    }
+
+    /// Check that `svg` is well-formed XML and return its start tags. Only the subset of XML the
+    /// generators write is accepted: no comments, CDATA or processing instructions.
+    fn parse_xml(svg: &str) -> Vec<Tag<'_>> {
+        let mut tags = Vec::new();
+        let mut open: Vec<&str> = Vec::new();
+        let mut rest = svg;
+        while let Some(start) = rest.find('<') {
+            check_text(&rest[..start]);
+            let end = start + rest[start..].find('>').expect("unclosed tag");
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop(), Some(name), "mismatched end tag");
+                continue;
+            }
+            let (tag, empty) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            let name = &tag[..name_end];
+            assert!(!name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == ':'));
+            let mut attributes = Vec::new();
+            let mut attrs = tag[name_end..].trim_start();
+            while !attrs.is_empty() {
+                // Facai v1 writes `in = "c"`.
+                let (attr, value) = attrs.split_once('=').expect("attribute without value");
+                let value = value
+                    .trim_start()
+                    .strip_prefix('"')
+                    .expect("unquoted attribute");
+                let attr = attr.trim_end();
+                assert!(attr
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == ':'));
+                let value_end = value.find('"').expect("unclosed attribute");
+                check_text(&value[..value_end]);
+                assert!(
+                    attributes.iter().all(|(other, _)| *other != attr),
+                    "duplicate attribute {}",
+                    attr
+                );
+                attributes.push((attr, &value[..value_end]));
+                attrs = value[value_end + 1..].trim_start();
+            }
+            if !empty {
+                open.push(name);
+            }
+            tags.push(Tag { name, attributes });
+        }
+        check_text(rest);
+        assert!(open.is_empty(), "unclosed elements {:?}", open);
+        assert_eq!(tags.first().map(|tag| tag.name), Some("svg"));
+        tags
+    }
+
+    fn check_text(text: &str) {
+        assert!(!text.contains('<') && !text.contains('>'));
+        for (i, _) in text.match_indices('&') {
+            let entity = &text[i..i + text[i..].find(';').expect("unterminated entity") + 1];
+            assert!(["&amp;", "&lt;", "&gt;", "&quot;", "&apos;"].contains(&entity));
+        }
+    }
+
+    fn attribute<'a>(tag: &Tag<'a>, name: &str) -> Option<&'a str> {
+        tag.attributes
+            .iter()
+            .find(|(attr, _)| *attr == name)
+            .map(|(_, value)| *value)
+    }
+
+    fn percent(value: &str) -> u32 {
+        value.strip_suffix('%').unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_properties() {
+        let every_option = Style {
+            palette: PaletteChoice::Seeded,
+            layers: LayerChoice::Seeded,
+            animated: true,
+            described: true,
+        };
+        for seed in random_seeds(0x2545f4914f6cdd1d).take(200) {
+            for (generator, style) in GENERATORS
+                .iter()
+                .flat_map(|generator| [(generator, &Style::CLASSIC), (generator, &every_option)])
+            {
+                let svg = generator.svg(&seed, style);
+                let tags = parse_xml(&svg);
+                let context = format!("{} {}", generator.name(), hex(&*seed));
+
+                let filters: Vec<&Tag> = tags.iter().filter(|tag| tag.name == "filter").collect();
+                let ids: Vec<&str> = filters
+                    .iter()
+                    .map(|filter| attribute(filter, "id").unwrap())
+                    .collect();
+                // Glitch bands, glyph shadows are drawn by a `<use>` as well.
+                let uses: Vec<&str> = tags
+                    .iter()
+                    .filter(|tag| tag.name == "use")
+                    .filter_map(|tag| attribute(tag, "filter"))
+                    .collect();
+                for (i, id) in ids.iter().enumerate() {
+                    assert!(
+                        !ids[..i].contains(id),
+                        "{}: duplicate filter {}",
+                        context,
+                        id
+                    );
+                    assert!(
+                        uses.contains(&format!("url(#{})", id).as_str()),
+                        "{}: unused filter {}",
+                        context,
+                        id
+                    );
+                }
+                assert_eq!(uses.len(), ids.len(), "{}", context);
+
+                // Bands tile the image from the top. The first generators take band heights
+                // straight from the seed, which can run past the bottom, their last bands are
+                // simply not visible. Normalised bands are never empty and end at the bottom.
+                let normalised = generator.version() >= crate::facai_gen::FACAI_V2.version;
+                let mut bottom = 0;
+                for filter in &filters {
+                    assert_eq!(
+                        percent(attribute(filter, "y").unwrap()),
+                        bottom,
+                        "{}",
+                        context
+                    );
+                    let height = percent(attribute(filter, "height").unwrap());
+                    assert!(!normalised || height > 0, "{}: empty band", context);
+                    bottom += height;
+                }
+                let seed_heights: u32 = (0..4).map(|i| (seed[i * 8] >> 2) as u32).sum();
+                let expected = if normalised {
+                    100
+                } else {
+                    seed_heights.max(100)
+                };
+                assert_eq!(bottom, expected, "{}: bands end at {}%", context, bottom);
+
+                let bands = generator
+                    .attributes(&seed, style)
+                    .iter()
+                    .find(|(trait_type, _)| *trait_type == "Glitch Bands")
+                    .map(|(_, value)| value.as_u64().unwrap());
+                assert_eq!(bands, Some(uses.len() as u64), "{}", context);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed_stream() {
+        let seed = Seed::new([7u8; 32]);
+        let bytes: Vec<u8> = seed.stream("glyph").take(80).collect();
+        assert_eq!(bytes, seed.stream("glyph").take(80).collect::<Vec<u8>>());
+        assert_eq!(
+            &bytes[..32],
+            &Sha256::digest([&[7u8; 32][..], b"glyph", &0u64.to_le_bytes()].concat())[..]
+        );
+        assert_ne!(bytes[..32], bytes[32..64]);
+        assert_ne!(bytes, seed.stream("stars").take(80).collect::<Vec<u8>>());
+        assert!(Seed::from_slice(&[0u8; 31]).is_none());
+        assert_eq!(Seed::from_slice(&[7u8; 32]), Some(seed));
+    }
+}
@@ -0,0 +1,67 @@
+//! What owners may stamp on their mooncakes. The contract checks inscriptions with these rules,
+//! and the layout in `facai_gen` is made to fit the widest of them.
+
+/// Widest inscription, counting ideographs as two latin letters: 4 characters of a blessing or
+/// 8 letters of a name.
+pub const MAX_WIDTH: usize = 8;
+
+/// Inscriptions are CJK ideographs, latin letters, digits, and single spaces, apostrophes,
+/// hyphens and dots between them, at most `MAX_WIDTH` wide.
+pub fn assert_valid(inscription: &str) {
+    assert!(!inscription.is_empty(), "Inscription is empty");
+    assert!(
+        inscription.chars().all(|c| c.is_ascii_alphanumeric()
+            || matches!(c, ' ' | '\'' | '-' | '.')
+            || ('\u{4e00}'..='\u{9fff}').contains(&c)),
+        "Inscription may only contain CJK ideographs, latin letters, digits, spaces and ' - ."
+    );
+    assert!(
+        inscription.trim() == inscription && !inscription.contains("  "),
+        "Inscription may not start or end with a space, or have consecutive spaces"
+    );
+    let width: usize = inscription
+        .chars()
+        .map(|c| if c.is_ascii() { 1 } else { 2 })
+        .sum();
+    assert!(
+        width <= MAX_WIDTH,
+        "Inscription is too long, at most {} ideographs or {} letters",
+        MAX_WIDTH / 2,
+        MAX_WIDTH
+    );
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid() {
+        for inscription in ["中秋快乐", "Mei Ling", "O'Neil-2", "阿Mei"] {
+            assert_valid(inscription);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        for inscription in [
+            "",
+            " Mei",
+            "Mei ",
+            "Ah  Mei",
+            "月圆人团圆",
+            "Grandma Wong",
+            "Ah Mei!",
+            "<b>",
+            "Zoë",
+            "🥮",
+            "中秋，快乐",
+        ] {
+            assert!(
+                std::panic::catch_unwind(|| assert_valid(inscription)).is_err(),
+                "{:?} is valid",
+                inscription
+            );
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! The mooncake art generators. They don't call NEAR host functions, so the contract, the render
+//! CLI and the browser preview all run the very same code.
+pub mod compose;
+pub mod edition;
+pub mod facai_gen;
+pub mod facai_text;
+pub mod generator;
+pub mod inscriptions;
+pub mod palette;
+pub mod svg;
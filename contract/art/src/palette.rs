@@ -0,0 +1,106 @@
+/// Colours of a mooncake. `classic` is the palette of the 2022 edition.
+#[derive(PartialEq)]
+pub struct Palette {
+    pub name: &'static str,
+    /// Side of the crust and its outline.
+    pub crust: &'static str,
+    pub crust_stroke: &'static str,
+    /// Embossed pattern on top of the crust.
+    pub pattern: &'static str,
+    /// Gradient stops of the crust top.
+    pub filling: [&'static str; 3],
+    /// Gradient stops of the raised rim.
+    pub rim: [&'static str; 2],
+    /// Glyph shadow and glyph.
+    pub glyph: [&'static str; 2],
+    pub highlight: &'static str,
+    /// Saturation and lightness of the glitch flood, in percent.
+    pub glitch_saturation: u8,
+    pub glitch_lightness: u8,
+}
+
+pub const CLASSIC: Palette = Palette {
+    name: "classic",
+    crust: "#7f3a01",
+    crust_stroke: "#d28827",
+    pattern: "#421f02",
+    filling: ["#ffbd44", "#dc8912", "#d07600"],
+    rim: ["#bf5802", "#9e3d06"],
+    glyph: ["#431d02", "#b14d04"],
+    highlight: "#fed1aa",
+    glitch_saturation: 85,
+    glitch_lightness: 50,
+};
+
+pub const LOTUS: Palette = Palette {
+    name: "lotus",
+    crust: "#8a4b12",
+    crust_stroke: "#e0a85a",
+    pattern: "#4a2a0a",
+    filling: ["#ffd98a", "#e8b04a", "#d99a2b"],
+    rim: ["#c98a3a", "#a86a1e"],
+    glyph: ["#4a2a0a", "#c06a1a"],
+    highlight: "#fff0d0",
+    glitch_saturation: 70,
+    glitch_lightness: 60,
+};
+
+pub const MATCHA: Palette = Palette {
+    name: "matcha",
+    crust: "#3f5a1e",
+    crust_stroke: "#9cc45a",
+    pattern: "#1f2e0c",
+    filling: ["#c8e68a", "#98c44a", "#7aa630"],
+    rim: ["#6a9a2a", "#4e7a1a"],
+    glyph: ["#1f2e0c", "#5e8a22"],
+    highlight: "#eef8d8",
+    glitch_saturation: 60,
+    glitch_lightness: 45,
+};
+
+pub const SNOW_SKIN: Palette = Palette {
+    name: "snow-skin",
+    crust: "#e8c8d0",
+    crust_stroke: "#ffffff",
+    pattern: "#b88a98",
+    filling: ["#fff6f8", "#fde0e8", "#f8c8d6"],
+    rim: ["#f4b8c8", "#e8a0b4"],
+    glyph: ["#b88a98", "#f0a8bc"],
+    highlight: "#ffffff",
+    glitch_saturation: 90,
+    glitch_lightness: 75,
+};
+
+/// Pick a palette from a seed byte, classic being the most common and snow-skin the rarest.
+pub fn by_seed_byte(byte: u8) -> &'static Palette {
+    match byte {
+        0..=127 => &CLASSIC,
+        128..=191 => &LOTUS,
+        192..=239 => &MATCHA,
+        _ => &SNOW_SKIN,
+    }
+}
+
+/// `palette` with a gilded crust and rim, for tokens that were gifted often.
+pub fn gilded(palette: &Palette) -> Palette {
+    Palette {
+        crust: "#a8740c",
+        crust_stroke: "#ffd86b",
+        pattern: "#6e4a06",
+        rim: ["#e8b030", "#b07a12"],
+        ..*palette
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_seed_byte() {
+        assert_eq!(by_seed_byte(0).name, "classic");
+        assert_eq!(by_seed_byte(128).name, "lotus");
+        assert_eq!(by_seed_byte(200).name, "matcha");
+        assert_eq!(by_seed_byte(255).name, "snow-skin");
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt::Display;
+
+/// A minimal SVG element tree. Attribute values are escaped, and elements are written
+/// without any whitespace between them, so the same tree always renders to the same bytes.
+pub struct Element {
+    name: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    children: Vec<Node>,
+}
+
+pub enum Node {
+    Element(Element),
+    /// Trusted markup, written as is.
+    Raw(String),
+    /// Character data, escaped.
+    Text(String),
+}
+
+impl Element {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn attr(mut self, name: &'static str, value: impl Display) -> Self {
+        self.attributes.push((name, value.to_string()));
+        self
+    }
+
+    pub fn child(mut self, child: Element) -> Self {
+        self.children.push(Node::Element(child));
+        self
+    }
+
+    pub fn raw(mut self, markup: String) -> Self {
+        self.children.push(Node::Raw(markup));
+        self
+    }
+
+    pub fn text(mut self, text: impl Display) -> Self {
+        self.children.push(Node::Text(text.to_string()));
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, false);
+        out
+    }
+
+    /// Like `render`, with a line break before every element and every end tag, as the 2022
+    /// generator wrote its static markup.
+    pub fn render_lines(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, true);
+        out
+    }
+
+    fn write(&self, out: &mut String, lines: bool) {
+        if lines {
+            out.push('\n');
+        }
+        out.push('<');
+        out.push_str(self.name);
+        for (name, value) in &self.attributes {
+            out.push(' ');
+            out.push_str(name);
+            out.push_str("=\"");
+            escape_into(value, out);
+            out.push('"');
+        }
+        if self.children.is_empty() {
+            out.push_str("/>");
+            return;
+        }
+        out.push('>');
+        for child in &self.children {
+            match child {
+                Node::Element(element) => element.write(out, lines),
+                Node::Raw(markup) => out.push_str(markup),
+                Node::Text(text) => escape_into(text, out),
+            }
+        }
+        if lines {
+            out.push('\n');
+        }
+        out.push_str("</");
+        out.push_str(self.name);
+        out.push('>');
+    }
+}
+
+fn escape_into(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// `data:` URI of an SVG document (RFC 2397). Every byte outside the unreserved and reserved
+/// URI characters of RFC 2396 is percent-encoded, `#` included, so the URI survives any browser.
+pub fn data_uri(svg: &str) -> String {
+    let mut uri = "data:image/svg+xml;charset=UTF-8,".to_string();
+    for byte in svg.bytes() {
+        match byte {
+            b'a'..=b'z'
+            | b'A'..=b'Z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')'
+            | b';'
+            | b'/'
+            | b'?'
+            | b':'
+            | b'@'
+            | b'&'
+            | b'='
+            | b'+'
+            | b'$'
+            | b',' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let svg = Element::new("g")
+            .attr("id", "a\"<b>&")
+            .child(Element::new("use").attr("href", "#fa"))
+            .raw("<path/>".to_string())
+            .child(Element::new("title").text("<发> & 月"))
+            .render();
+        assert_eq!(
+            svg,
+            r##"<g id="a&quot;&lt;b&gt;&amp;"><use href="#fa"/><path/><title>&lt;发&gt; &amp; 月</title></g>"##
+        );
+        let svg = Element::new("g")
+            .child(Element::new("path").attr("d", "M0 0"))
+            .render_lines();
+        assert_eq!(svg, "\n<g>\n<path d=\"M0 0\"/>\n</g>");
+    }
+
+    #[test]
+    fn test_data_uri() {
+        assert_eq!(
+            data_uri(r##"<svg a="#f 50%">月</svg>"##),
+            "data:image/svg+xml;charset=UTF-8,%3Csvg%20a=%22%23f%2050%25%22%3E%E6%9C%88%3C/svg%3E"
+        );
+    }
+}
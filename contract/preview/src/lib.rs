@@ -0,0 +1,42 @@
+//! Browser build of the mooncake art generators, for live previews in the frontend.
+//!
+//! The contract crate can't be built for the browser, its `near_bindgen` methods import the NEAR
+//! host functions. This package links the same `mooncake-art` generators and edition table
+//! instead, so a preview is byte-identical to what the contract renders for the same seed.
+use mooncake_art::edition::{self, Edition};
+use mooncake_art::generator::{self, Seed};
+use wasm_bindgen::prelude::*;
+
+/// SVG of the mooncake `seed` mints in `edition`, the newest edition by default.
+#[wasm_bindgen]
+pub fn render(seed: &[u8], edition: Option<String>) -> Result<String, JsError> {
+    let (edition, seed) = resolve(seed, edition)?;
+    Ok(generator::by_version(edition.generator).svg(&seed, &edition.style))
+}
+
+/// Trait attributes of the mooncake `seed` mints in `edition`, as the JSON stored in
+/// `TokenMetadata.extra`.
+#[wasm_bindgen]
+pub fn traits(seed: &[u8], edition: Option<String>) -> Result<String, JsError> {
+    let (edition, seed) = resolve(seed, edition)?;
+    let attributes = generator::by_version(edition.generator).attributes(&seed, &edition.style);
+    Ok(generator::attributes_document(&attributes).to_string())
+}
+
+/// Id of the newest edition, the one `render` and `traits` default to.
+#[wasm_bindgen]
+pub fn latest_edition() -> String {
+    edition::latest().id.to_string()
+}
+
+fn resolve(seed: &[u8], edition: Option<String>) -> Result<(&'static Edition, Seed), JsError> {
+    let seed = Seed::from_slice(seed)
+        .ok_or_else(|| JsError::new(&format!("Seeds are {} bytes", Seed::LEN)))?;
+    let edition = match edition {
+        Some(id) => {
+            edition::get(&id).ok_or_else(|| JsError::new(&format!("Unknown edition {}", id)))?
+        }
+        None => edition::latest(),
+    };
+    Ok((edition, seed))
+}
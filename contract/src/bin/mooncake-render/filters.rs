@@ -0,0 +1,44 @@
+//! What the generators emit that resvg can't paint the way browsers do. The filter primitives
+//! they use are all implemented by resvg, and are checked against browser screenshots of the
+//! goldens instead, see `tests/browser`.
+
+/// Markup of the art, and how resvg's PNGs of it differ from what browsers show.
+const UNSUPPORTED: &[(&str, &str)] = &[
+    (
+        "<animate ",
+        "SMIL <animate>: resvg ignores animations and paints the static values, browsers animate them",
+    ),
+    (
+        "<text ",
+        "<text>: resvg paints inscriptions with the fonts installed here, browsers with their own",
+    ),
+];
+
+/// Everything of `UNSUPPORTED` that `svg` uses, with how often.
+pub fn unsupported(svg: &str) -> Vec<String> {
+    UNSUPPORTED
+        .iter()
+        .filter_map(|(markup, description)| match svg.matches(markup).count() {
+            0 => None,
+            count => Some(format!("{} ({} elements)", description, count)),
+        })
+        .collect()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use mooncake_nft::preview::{self, Seed};
+
+    #[test]
+    fn test_unsupported() {
+        let seed = Seed::new([0u8; 32]);
+        let classic = preview::render_svg(&seed, preview::edition("2022").unwrap());
+        assert_eq!(unsupported(&classic), Vec::<String>::new());
+
+        let animated = preview::render_svg(&seed, preview::edition("2027").unwrap());
+        let divergences = unsupported(&animated);
+        assert_eq!(divergences.len(), 1);
+        assert!(divergences[0].starts_with("SMIL <animate>"));
+    }
+}
@@ -0,0 +1,314 @@
+//! Render mooncake art off-chain, through the same generators as the contract.
+//!
+//! ```text
+//! cargo run --features render --bin mooncake-render -- [OPTIONS] [SEED | TOKEN_ID]...
+//! ```
+//!
+//! Build with `--features png` to also rasterise the art with resvg.
+mod filters;
+#[cfg(feature = "png")]
+mod png;
+#[cfg(not(feature = "png"))]
+mod png {
+    pub enum Rasteriser {}
+
+    impl Rasteriser {
+        pub fn new() -> Result<Self, String> {
+            Err("--png needs the png feature".to_string())
+        }
+
+        pub fn write_png(&self, _: &str, _: u32, _: &std::path::Path) -> Result<(), String> {
+            match *self {}
+        }
+    }
+}
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mooncake_nft::preview::{self, Edition, Seed};
+use near_sdk::bs58;
+use near_sdk::serde_json::{json, Value};
+
+const USAGE: &str = "Usage: mooncake-render [OPTIONS] [SEED | TOKEN_ID]...
+
+Renders each seed, given as 64 hex digits or bs58, or the seed of a token id such as
+2022-<bs58 seed>, to <out>/<name>.svg. Without seeds, renders --count random seeds.
+
+Options:
+  -n, --count <N>          Random seeds to render without seeds given [default: 10]
+  -o, --out <DIR>          Output directory [default: render]
+  -e, --edition <ID>       Edition to render seeds in [default: the latest]
+      --traits             Also write the trait attributes of every seed to traits.json
+      --contact-sheet      Also write an index.html page showing every render
+      --png <SIZES>        Also write <out>/<name>-<size>.png at each comma separated size,
+                           e.g. 256,512,1080 (needs the png feature)
+      --check-filters      Fail when a render uses what resvg can't paint the way browsers do,
+                           such as animations
+  -h, --help               Print this help";
+
+struct Options {
+    inputs: Vec<String>,
+    count: usize,
+    out: PathBuf,
+    edition: Option<&'static Edition>,
+    traits: bool,
+    contact_sheet: bool,
+    png_sizes: Vec<u32>,
+    check_filters: bool,
+}
+
+struct Render {
+    name: String,
+    seed: Seed,
+    edition: &'static Edition,
+}
+
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+    if let Err(error) = run(&options) {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        inputs: Vec::new(),
+        count: 10,
+        out: PathBuf::from("render"),
+        edition: None,
+        traits: false,
+        contact_sheet: false,
+        png_sizes: Vec::new(),
+        check_filters: false,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "-n" | "--count" => {
+                options.count = value(&arg)?
+                    .parse()
+                    .map_err(|_| "--count must be a number".to_string())?
+            }
+            "-o" | "--out" => options.out = PathBuf::from(value(&arg)?),
+            "-e" | "--edition" => {
+                let id = value(&arg)?;
+                options.edition =
+                    Some(preview::edition(&id).ok_or(format!("Unknown edition {}", id))?)
+            }
+            "--traits" => options.traits = true,
+            "--contact-sheet" => options.contact_sheet = true,
+            "--png" => {
+                options.png_sizes = value(&arg)?
+                    .split(',')
+                    .map(|size| match size.trim().parse() {
+                        Ok(size) if size > 0 => Ok(size),
+                        _ => Err(format!("Invalid --png size {}", size)),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            "--check-filters" => options.check_filters = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+            _ => options.inputs.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let edition = options.edition.unwrap_or_else(preview::latest_edition);
+    let renders = if options.inputs.is_empty() {
+        random_seeds(options.count)
+            .into_iter()
+            .map(|seed| Render {
+                name: hex(&*seed),
+                seed,
+                edition,
+            })
+            .collect()
+    } else {
+        options
+            .inputs
+            .iter()
+            .map(|input| parse_input(input, edition))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let rasteriser = if options.png_sizes.is_empty() {
+        None
+    } else {
+        Some(png::Rasteriser::new()?)
+    };
+
+    fs::create_dir_all(&options.out).map_err(|e| e.to_string())?;
+    let mut traits = Vec::new();
+    let mut divergences = Vec::new();
+    for render in &renders {
+        let svg = preview::render_svg(&render.seed, render.edition);
+        let path = options.out.join(format!("{}.svg", render.name));
+        fs::write(&path, &svg).map_err(|e| format!("{}: {}", path.display(), e))?;
+        println!("{}", path.display());
+        if let Some(rasteriser) = &rasteriser {
+            for size in &options.png_sizes {
+                let path = options.out.join(format!("{}-{}.png", render.name, size));
+                rasteriser.write_png(&svg, *size, &path)?;
+                println!("{}", path.display());
+            }
+        }
+        if options.check_filters {
+            for divergence in filters::unsupported(&svg) {
+                divergences.push(format!("{}: {}", render.name, divergence));
+            }
+        }
+        if options.traits || options.contact_sheet {
+            traits.push(trait_entry(render));
+        }
+    }
+    if options.traits {
+        let path = options.out.join("traits.json");
+        let json = near_sdk::serde_json::to_string_pretty(&traits).unwrap();
+        fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))?;
+        println!("{}", path.display());
+    }
+    if options.contact_sheet {
+        let path = options.out.join("index.html");
+        fs::write(&path, contact_sheet(&traits))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        println!("{}", path.display());
+    }
+    if !divergences.is_empty() {
+        return Err(format!(
+            "resvg paints these differently from browsers:\n  {}",
+            divergences.join("\n  ")
+        ));
+    }
+    Ok(())
+}
+
+/// A token id keeps its edition, bare seeds use `edition`.
+fn parse_input(input: &str, edition: &'static Edition) -> Result<Render, String> {
+    if input.contains('-') {
+        let (seed, edition) = preview::token_seed(input)
+            .ok_or(format!("{} is not the id of a generated token", input))?;
+        return Ok(Render {
+            name: input.to_string(),
+            seed,
+            edition,
+        });
+    }
+    let bytes: Vec<u8> =
+        if input.len() == Seed::LEN * 2 && input.chars().all(|c| c.is_ascii_hexdigit()) {
+            (0..input.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&input[i..i + 2], 16).unwrap())
+                .collect()
+        } else {
+            bs58::decode(input)
+                .with_alphabet(bs58::Alphabet::BITCOIN)
+                .into_vec()
+                .map_err(|_| format!("{} is neither hex nor bs58", input))?
+        };
+    let seed = Seed::from_slice(&bytes).ok_or(format!(
+        "{} is {} bytes, seeds are {} bytes",
+        input,
+        bytes.len(),
+        Seed::LEN
+    ))?;
+    Ok(Render {
+        name: hex(&*seed),
+        seed,
+        edition,
+    })
+}
+
+/// Seeds for design review only, expanded from the clock. Their hex names can be passed back in
+/// to render them again.
+fn random_seeds(count: usize) -> Vec<Seed> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let mut base = [0u8; Seed::LEN];
+    base[..16].copy_from_slice(&nanos.to_le_bytes());
+    let bytes: Vec<u8> = Seed::new(base)
+        .stream("mooncake-render")
+        .take(count * Seed::LEN)
+        .collect();
+    bytes
+        .chunks(Seed::LEN)
+        .map(|chunk| Seed::from_slice(chunk).unwrap())
+        .collect()
+}
+
+fn trait_entry(render: &Render) -> Value {
+    let attributes: Vec<Value> = preview::attributes(&render.seed, render.edition)
+        .into_iter()
+        .map(|(trait_type, value)| json!({ "trait_type": trait_type, "value": value }))
+        .collect();
+    json!({
+        "name": render.name,
+        "file": format!("{}.svg", render.name),
+        "seed": hex(&*render.seed),
+        "edition": render.edition.id,
+        "version": render.edition.generator,
+        "generator": preview::generator_name(render.edition),
+        "attributes": attributes,
+    })
+}
+
+fn contact_sheet(traits: &[Value]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Mooncake renders</title>\n<style>\
+body{font-family:sans-serif;margin:16px;background:#f4f0ea}\
+main{display:grid;grid-template-columns:repeat(auto-fill,minmax(240px,1fr));gap:16px}\
+figure{margin:0;background:#fff;padding:8px}img{width:100%}\
+figcaption{font-size:12px;word-break:break-all}dl{margin:4px 0 0}dt{font-weight:bold;float:left;margin-right:4px}\
+</style></head><body><main>\n",
+    );
+    for entry in traits {
+        let name = entry["name"].as_str().unwrap_or_default();
+        html.push_str(&format!(
+            "<figure><img src=\"{}\" loading=\"lazy\"><figcaption>{} (v{})<dl>",
+            escape(entry["file"].as_str().unwrap_or_default()),
+            escape(name),
+            entry["version"]
+        ));
+        if let Some(attributes) = entry["attributes"].as_array() {
+            for attribute in attributes {
+                let value = match &attribute["value"] {
+                    Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                };
+                html.push_str(&format!(
+                    "<dt>{}</dt><dd>{}</dd>",
+                    escape(attribute["trait_type"].as_str().unwrap_or_default()),
+                    escape(&value)
+                ));
+            }
+        }
+        html.push_str("</dl></figcaption></figure>\n");
+    }
+    html.push_str("</main></body></html>\n");
+    html
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,102 @@
+//! PNG rasterisation with resvg, for wallets and social previews that can't show SVG filters.
+use std::path::Path;
+
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg;
+
+pub struct Rasteriser {
+    options: usvg::Options<'static>,
+}
+
+impl Rasteriser {
+    pub fn new() -> Result<Self, String> {
+        let mut options = usvg::Options::default();
+        // Inscriptions are drawn with <text>, CJK ones need a CJK serif font installed.
+        options.fontdb_mut().load_system_fonts();
+        Ok(Self { options })
+    }
+
+    /// Render `svg` scaled to a `size` x `size` square.
+    pub fn render(&self, svg: &str, size: u32) -> Result<Pixmap, String> {
+        let tree = usvg::Tree::from_str(svg, &self.options).map_err(|e| e.to_string())?;
+        let mut pixmap = Pixmap::new(size, size).ok_or(format!("Invalid size {}", size))?;
+        let scale = size as f32 / tree.size().width().max(tree.size().height());
+        resvg::render(
+            &tree,
+            Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+        Ok(pixmap)
+    }
+
+    pub fn write_png(&self, svg: &str, size: u32, path: &Path) -> Result<(), String> {
+        self.render(svg, size)?
+            .save_png(path)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_render() {
+        let rasteriser = Rasteriser::new().unwrap();
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><rect width="5" height="10" fill="#ff0000"/></svg>"##;
+        let pixmap = rasteriser.render(svg, 20).unwrap();
+        let pixel = pixmap.pixel(5, 10).unwrap();
+        assert_eq!(
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()],
+            [255, 0, 0, 255]
+        );
+        assert_eq!(pixmap.pixel(15, 10).unwrap().alpha(), 0);
+    }
+
+    /// Every PNG in `tests/browser` is a browser's screenshot of the golden of the same name,
+    /// see the README there. resvg has to paint the golden the same, up to anti-aliasing, unless
+    /// it uses something `filters::unsupported` lists.
+    #[test]
+    fn test_browser_references() {
+        let rasteriser = Rasteriser::new().unwrap();
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for entry in fs::read_dir(root.join("tests/browser")).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension() != Some("png".as_ref()) {
+                continue;
+            }
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            let golden = root.join("art/tests/golden").join(format!("{}.svg", name));
+            let svg = fs::read_to_string(&golden)
+                .unwrap_or_else(|_| panic!("{} has no golden {}", name, golden.display()));
+            if !crate::filters::unsupported(&svg).is_empty() {
+                continue;
+            }
+            let reference = Pixmap::load_png(&path).unwrap();
+            let painted = rasteriser.render(&svg, reference.width()).unwrap();
+            let differing = painted
+                .pixels()
+                .iter()
+                .zip(reference.pixels())
+                .filter(|(a, b)| {
+                    let (a, b) = (a.demultiply(), b.demultiply());
+                    [
+                        (a.red(), b.red()),
+                        (a.green(), b.green()),
+                        (a.blue(), b.blue()),
+                        (a.alpha(), b.alpha()),
+                    ]
+                    .iter()
+                    .any(|(a, b)| a.abs_diff(*b) > 8)
+                })
+                .count();
+            assert!(
+                differing * 100 <= painted.pixels().len(),
+                "{}: {} pixels differ from the browser",
+                name,
+                differing
+            );
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Editions as the contract stores them. The table itself is shared with the preview, see
+//! `mooncake_art::edition`.
+use near_sdk::env;
+
+pub use mooncake_art::edition::{get, latest, of_token, Edition, EDITIONS};
+use mooncake_art::generator::Style;
+
+use crate::media::MediaEncoding;
+
+/// How the art of `edition` is put into `TokenMetadata`. The 2022 edition keeps the encoding its
+/// tokens were minted with.
+pub fn encoding(edition: &Edition) -> MediaEncoding {
+    match edition.id {
+        "2022" => MediaEncoding::LegacyPercentEncoded,
+        _ => MediaEncoding::PercentEncoded,
+    }
+}
+
+/// Encoding of the media of `token_id`, percent-encoded for tokens outside of any edition.
+pub fn media_encoding(token_id: &str) -> MediaEncoding {
+    of_token(token_id).map_or(MediaEncoding::PercentEncoded, encoding)
+}
+
+/// Style the art of `token_id` is drawn in, the classic one for tokens outside of any edition.
+pub fn style(token_id: &str) -> &'static Style {
+    of_token(token_id).map_or(&Style::CLASSIC, |edition| &edition.style)
+}
+
+/// Whether `edition` isn't revealed yet.
+pub fn is_edition_sealed(edition: &Edition) -> bool {
+    edition.is_sealed_at(env::block_timestamp())
+}
+
+/// Whether `token_id` belongs to an edition that isn't revealed yet.
+pub fn is_sealed(token_id: &str) -> bool {
+    of_token(token_id).is_some_and(is_edition_sealed)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_is_sealed() {
+        let reveal_at = get("2027").unwrap().reveal_at.unwrap();
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_timestamp(reveal_at - 1).build());
+        assert!(is_sealed("2027-1"));
+        assert!(!is_sealed("2022-abc"));
+        assert!(!is_sealed("custom"));
+        testing_env!(context.block_timestamp(reveal_at).build());
+        assert!(!is_sealed("2027-1"));
+        assert_eq!(latest().id, "2027");
+        assert_eq!(
+            media_encoding("2022-abc"),
+            MediaEncoding::LegacyPercentEncoded
+        );
+        assert_eq!(media_encoding("2027-1"), MediaEncoding::PercentEncoded);
+    }
+}
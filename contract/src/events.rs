@@ -0,0 +1,26 @@
+use near_sdk::env;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::json;
+
+/// Tokens whose metadata changed after mint, so indexers and marketplaces fetch it again.
+/// Logged in the NEP-297 format under the nep171 standard, like the mint, transfer and burn
+/// events of `near_contract_standards`.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMetadataUpdate<'a> {
+    pub token_ids: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+impl NftMetadataUpdate<'_> {
+    pub fn emit(self) {
+        let event = json!({
+            "standard": "nep171",
+            "version": "1.0.0",
+            "event": "nft_metadata_update",
+            "data": [self],
+        });
+        env::log_str(&format!("EVENT_JSON:{}", event));
+    }
+}
@@ -0,0 +1,68 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::IntoStorageKey;
+
+/// Gift counts at which the art of a token evolves, see `generator::Extras::evolution`.
+pub const THRESHOLDS: [u32; 3] = [2, 5, 10];
+
+/// How often each token was gifted, counting only the gifts karma credits. Kept apart from
+/// `karma::Karma`, so resetting karma doesn't undo the evolution of the art.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Gifts {
+    count_by_token_id: LookupMap<TokenId, u32>,
+}
+
+impl Gifts {
+    pub fn new<Q>(count_by_token_id_prefix: Q) -> Self
+    where
+        Q: IntoStorageKey,
+    {
+        Self {
+            count_by_token_id: LookupMap::new(count_by_token_id_prefix),
+        }
+    }
+
+    /// Count a gift of `token_id`. Returns `true` when the art evolved with it.
+    pub fn add(&mut self, token_id: &TokenId) -> bool {
+        let count = self.count(token_id) + 1;
+        self.count_by_token_id.insert(token_id, &count);
+        THRESHOLDS.contains(&count)
+    }
+
+    pub fn count(&self, token_id: &TokenId) -> u32 {
+        self.count_by_token_id.get(token_id).unwrap_or(0)
+    }
+
+    pub fn remove(&mut self, token_id: &TokenId) {
+        self.count_by_token_id.remove(token_id);
+    }
+
+    /// Number of thresholds the gift count of `token_id` has passed.
+    pub fn evolution(&self, token_id: &TokenId) -> u8 {
+        let count = self.count(token_id);
+        THRESHOLDS
+            .iter()
+            .filter(|&&threshold| count >= threshold)
+            .count() as u8
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evolution() {
+        let mut gifts = Gifts::new(b"g");
+        let token_id = "2022-a".to_string();
+        let evolved: Vec<bool> = (0..11).map(|_| gifts.add(&token_id)).collect();
+        assert_eq!(
+            evolved,
+            [false, true, false, false, true, false, false, false, false, true, false]
+        );
+        assert_eq!(gifts.count(&token_id), 11);
+        assert_eq!(gifts.evolution(&token_id), 3);
+        assert_eq!(gifts.evolution(&"2022-b".to_string()), 0);
+    }
+}
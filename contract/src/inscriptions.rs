@@ -0,0 +1,56 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::IntoStorageKey;
+
+use mooncake_art::inscriptions::assert_valid;
+
+/// Text owners stamped on their mooncakes. Every token can be inscribed once.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Inscriptions {
+    inscription_by_token_id: LookupMap<TokenId, String>,
+}
+
+impl Inscriptions {
+    pub fn new<Q>(inscription_by_token_id_prefix: Q) -> Self
+    where
+        Q: IntoStorageKey,
+    {
+        Self {
+            inscription_by_token_id: LookupMap::new(inscription_by_token_id_prefix),
+        }
+    }
+
+    pub fn insert(&mut self, token_id: &TokenId, inscription: &str) {
+        assert_valid(inscription);
+        assert!(
+            self.inscription_by_token_id
+                .insert(token_id, &inscription.to_string())
+                .is_none(),
+            "Token is already inscribed"
+        );
+    }
+
+    pub fn get(&self, token_id: &TokenId) -> Option<String> {
+        self.inscription_by_token_id.get(token_id)
+    }
+
+    pub fn remove(&mut self, token_id: &TokenId) {
+        self.inscription_by_token_id.remove(token_id);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Token is already inscribed")]
+    fn test_once() {
+        let mut inscriptions = Inscriptions::new(b"i");
+        let token_id = "2022-a".to_string();
+        inscriptions.insert(&token_id, "福");
+        assert_eq!(inscriptions.get(&token_id), Some("福".to_string()));
+        inscriptions.insert(&token_id, "禄");
+    }
+}
@@ -13,12 +13,6 @@ pub struct Quota {
     lasted_used_at: u64,
 }
 
-#[derive(BorshDeserialize, BorshSerialize)]
-pub struct RankItem {
-    account: AccountId,
-    balance: Balance,
-}
-
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Karma {
     balance_by_owner: LookupMap<AccountId, Balance>,
@@ -39,7 +33,9 @@ impl Karma {
         }
     }
 
-    pub fn increase(&mut self, account_id: &AccountId, token_id: &TokenId) {
+    /// Credit `account_id` for gifting `token_id`, unless the quota of the token is used up.
+    /// Returns whether the gift was credited.
+    pub fn increase(&mut self, account_id: &AccountId, token_id: &TokenId) -> bool {
         let mut quota = match self.quota_by_token_id.get(token_id) {
             Some(quota) => quota,
             None => Quota {
@@ -64,11 +60,17 @@ impl Karma {
             quota.lasted_used_at = env::block_timestamp();
             self.quota_by_token_id.insert(token_id, &quota);
             self.increase_internal(account_id);
+            return true;
         }
+        false
+    }
+
+    pub fn remove_quota(&mut self, token_id: &TokenId) {
+        self.quota_by_token_id.remove(token_id);
     }
 
     fn increase_internal(&mut self, account_id: &AccountId) {
-        let mut balance = self.balance_by_owner.get(account_id).unwrap_or_else(|| 0);
+        let mut balance = self.balance_by_owner.get(account_id).unwrap_or(0);
         balance += 1;
         self.balance_by_owner.insert(account_id, &balance);
 
@@ -86,13 +88,12 @@ impl Karma {
             return;
         }
 
-        let rank_min = self.rank.get(0).map_or(0, |item| item.0);
+        let rank_min = self.rank.first().map_or(0, |item| item.0);
         if balance >= rank_min {
             if balance > rank_min {
                 self.rank[0] = (balance, account_id.clone());
             }
             self.rank.sort();
-            return;
         }
     }
 
@@ -118,7 +119,7 @@ mod tests {
             karma.quota_by_token_id.get(&token_id).map(|q| q.value),
             Some(2)
         );
-        assert_eq!(karma.rank.get(0), Some(&(1, accounts(0))));
+        assert_eq!(karma.rank.first(), Some(&(1, accounts(0))));
 
         karma.increase(&accounts(0), &token_id);
         assert_eq!(karma.balance_by_owner.get(&accounts(0)), Some(2));
@@ -126,7 +127,7 @@ mod tests {
             karma.quota_by_token_id.get(&token_id).map(|q| q.value),
             Some(1)
         );
-        assert_eq!(karma.rank.get(0), Some(&(1, accounts(0))));
+        assert_eq!(karma.rank.first(), Some(&(1, accounts(0))));
 
         karma.increase(&accounts(0), &token_id);
         assert_eq!(karma.balance_by_owner.get(&accounts(0)), Some(3));
@@ -134,7 +135,7 @@ mod tests {
             karma.quota_by_token_id.get(&token_id).map(|q| q.value),
             Some(0)
         );
-        assert_eq!(karma.rank.get(0), Some(&(1, accounts(0))));
+        assert_eq!(karma.rank.first(), Some(&(1, accounts(0))));
 
         karma.increase(&accounts(0), &token_id);
         assert_eq!(karma.balance_by_owner.get(&accounts(0)), Some(3));
@@ -142,7 +143,7 @@ mod tests {
             karma.quota_by_token_id.get(&token_id).map(|q| q.value),
             Some(0)
         );
-        assert_eq!(karma.rank.get(0), Some(&(1, accounts(0))));
+        assert_eq!(karma.rank.first(), Some(&(1, accounts(0))));
     }
 
     #[test]
@@ -15,31 +15,51 @@ NOTES:
   - To prevent the deployed contract from being modified or deleted, it should not have any access
     keys on its account.
 */
-use near_contract_standards::non_fungible_token::approval;
 use near_contract_standards::non_fungible_token::core::NonFungibleTokenCore;
 use near_contract_standards::non_fungible_token::core::NonFungibleTokenResolver;
-use near_contract_standards::non_fungible_token::events::NftMint;
+use near_contract_standards::non_fungible_token::enumeration::NonFungibleTokenEnumeration;
+use near_contract_standards::non_fungible_token::events::{NftBurn, NftMint};
 use near_contract_standards::non_fungible_token::metadata::{
     NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata, NFT_METADATA_SPEC,
 };
-use near_contract_standards::non_fungible_token::NonFungibleToken;
+use near_contract_standards::non_fungible_token::{
+    refund_approved_account_ids, refund_deposit, NonFungibleToken,
+};
 use near_contract_standards::non_fungible_token::{Token, TokenId};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::bs58;
-use near_sdk::collections::LazyOption;
+use near_sdk::collections::{LazyOption, LookupMap};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::Serialize;
 use near_sdk::{
-    env, near_bindgen, AccountId, Balance, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue,
+    assert_one_yocto, env, near_bindgen, AccountId, Balance, BorshStorageKey, Gas, PanicOnDefault,
+    Promise, PromiseOrValue,
 };
+use std::collections::BTreeMap;
+
+use mooncake_art::{generator, palette, svg};
 
-mod facai_gen;
+mod edition;
+mod events;
+mod gifts;
+mod inscriptions;
 mod karma;
+mod lineage;
 mod linkdrop;
+mod media;
+mod placeholder;
+pub mod preview;
+mod rarity;
+mod seeds;
+mod tickets;
 
+/// State of the 2022 contract, see `migrate_2026_10_18`.
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct OldContract {
     tokens: NonFungibleToken,
     metadata: LazyOption<NFTContractMetadata>,
     karma: karma::Karma,
+    linkdrop: LookupMap<String, linkdrop::Linkdrop>,
 }
 
 #[near_bindgen]
@@ -49,9 +69,45 @@ pub struct Contract {
     metadata: LazyOption<NFTContractMetadata>,
     karma: karma::Karma,
     linkdrop: linkdrop::Linkdrops,
+    seeds: seeds::Seeds,
+    mint_tickets: tickets::MintTickets,
+    rarity: rarity::Rarity,
+    inscriptions: inscriptions::Inscriptions,
+    gifts: gifts::Gifts,
+    lineage: lineage::Lineage,
 }
 
 const DATA_IMAGE_SVG_NEAR_ICON: &str = "data:image/svg+xml;charset=UTF-8,%3csvg viewBox='0 0 1077 1080' xmlns='http://www.w3.org/2000/svg' xml:space='preserve' fill-rule='evenodd' clip-rule='evenodd' stroke-linecap='round' stroke-linejoin='round' stroke-miterlimit='1.6'%3e%3cpath fill='none' d='M0 0h1076v1079H0z'/%3e%3cpath d='M650 6169v19H544c13-41 75-74 159-81l-1 123-231-1c19-93 134-165 273-165 140 0 256 74 273 169l-231-2s-4-117-3-123c81 8 144 41 155 82l-98-1v-24' fill='none' stroke='gray' stroke-width='27.3' transform='matrix(1.00905 0 0 1.04494 -206 -6074)'/%3e%3cpath d='M650 6169v19H544c13-41 75-74 159-81l-1 123-231-1c19-93 134-165 273-165 140 0 256 74 273 169l-231-2-3-123c81 8 144 41 155 82l-98-1v-24' fill='none' stroke='gray' stroke-width='27.3' transform='matrix(-1.009 .01008 -.01043 -1.04488 1359 6998)'/%3e%3cpath d='M504 363h80a14 14 0 0 0 0-29h-80a14 14 0 0 0 0 29ZM585 569l-79-1a14 14 0 0 0 0 29h79a14 14 0 0 0 0-28ZM735 491v-47a14 14 0 0 0-29 0v47a14 14 0 0 0 29 0ZM385 484v-46a14 14 0 0 0-29-1v47a14 14 0 0 0 29 0Z' fill='gray'/%3e%3cpath d='M474 3843c-13 5-28 7-43 7-42 0-79-20-97-51v-205c18 30 54 51 97 51 15 0 29-3 42-7l1 205ZM858 3539c9-2 17-5 26-10 36-19 57-56 54-93 8-13 13-28 14-45v189a108 108 0 0 1-14 62c3 36-17 73-54 93-9 4-17 7-26 9v-205ZM858 3744c-7 25-25 48-51 61-19 10-39 14-58 12l-1-206a102 102 0 0 0 110-73v206Z' fill='none' stroke='gray' stroke-width='32.9' transform='translate(-11 -2900)'/%3e%3cpath d='M749 3817c-14 21-37 37-65 43-26 6-52 1-73-11v-205c21 12 47 16 73 11 28-6 50-23 64-44l1 206ZM611 3849c-17 14-40 23-65 23-29 0-54-11-72-29l-1-205c18 17 44 29 73 29 25 0 48-9 65-23v205Z' fill='none' stroke='gray' stroke-width='32.9' transform='translate(-11 -2900)'/%3e%3cpath d='M334 3799c-48-2-88-40-93-89v-205c5 48 44 87 93 89v205Z' fill='none' stroke='gray' stroke-width='32.9' transform='translate(-12 -2900)'/%3e%3cpath d='M241 3710a101 101 0 0 1-61-97c-7-14-11-29-12-45h0v-1a107 107 0 0 1 0-8v-193c1 15 5 29 12 41v4c0 42 25 78 61 94v205Z' fill='none' stroke='gray' stroke-width='32.9' transform='translate(-12 -2901)'/%3e%3cpath d='M528 3074c18-15 43-24 69-23 31 1 58 15 76 37 19-4 40-2 60 7 23 10 41 28 52 49a99 99 0 0 1 89 79 98 98 0 0 1 59 106 105 105 0 0 1 0 104c2 36-18 73-55 93-8 4-17 7-26 9-7 25-25 47-51 61-19 10-39 14-58 12-14 21-37 37-65 43-26 6-52 1-73-11-17 14-40 23-65 23-28 0-54-11-72-29-13 5-28 7-43 7-42 0-79-20-97-51-48-2-87-40-93-89a101 101 0 0 1-61-98 103 103 0 0 1 17-121c2-48 36-87 81-96 8-24 27-47 53-61 21-12 43-17 64-15 13-21 35-38 61-45 28-7 56-3 78 9Z' fill='none' stroke='gray' stroke-width='32.9' transform='translate(-7 -2895)'/%3e%3c/svg%3e";
+const MINT_PRICE: Balance = 1_000_000_000_000_000_000_000_000;
+const MAX_BATCH_MINT: usize = 20;
+/// Gas reserved for each token of a batch mint. `test_batch_mint_gas` measures the host calls
+/// of a mint at about 4.3 Tgas for 2022 and 6.2 Tgas for 2027; the rest is headroom for the wasm
+/// instructions, which the mocked runtime does not count. A full batch stays within the 300 Tgas
+/// a transaction can attach.
+const GAS_FOR_MINT: Gas = Gas(10_000_000_000_000);
+
+/// Progress of a method working through the tokens a page at a time.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Page {
+    /// Tokens of the page that were changed.
+    pub updated: u64,
+    /// Pass it as `from_token_id` to process the next page. `None` once every token was.
+    pub last_token_id: Option<TokenId>,
+}
+
+impl Page {
+    fn new(updated: u64, token_ids: &[TokenId], limit: usize) -> Self {
+        Self {
+            updated,
+            last_token_id: match token_ids.len() < limit {
+                true => None,
+                false => token_ids.last().cloned(),
+            },
+        }
+    }
+}
+
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
     NonFungibleToken,
@@ -62,6 +118,16 @@ enum StorageKey {
     Karma,
     KarmaQuota,
     LinkdropPending,
+    TokenSeed,
+    MintTicket,
+    RaritySupply,
+    RarityTrait,
+    Inscription,
+    GiftCount,
+    Parents,
+    LinkdropPubKeys,
+    EditionSerial,
+    RarityBucket,
 }
 
 #[near_bindgen]
@@ -98,18 +164,190 @@ impl Contract {
             ),
             metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
             karma: karma::Karma::new(StorageKey::Karma, StorageKey::KarmaQuota),
-            linkdrop: linkdrop::Linkdrops::new(StorageKey::LinkdropPending),
+            linkdrop: linkdrop::Linkdrops::new(
+                StorageKey::LinkdropPending,
+                StorageKey::LinkdropPubKeys,
+            ),
+            seeds: seeds::Seeds::new(StorageKey::TokenSeed, StorageKey::EditionSerial),
+            mint_tickets: tickets::MintTickets::new(StorageKey::MintTicket),
+            rarity: rarity::Rarity::new(
+                StorageKey::RaritySupply,
+                StorageKey::RarityTrait,
+                StorageKey::RarityBucket,
+            ),
+            inscriptions: inscriptions::Inscriptions::new(StorageKey::Inscription),
+            gifts: gifts::Gifts::new(StorageKey::GiftCount),
+            lineage: lineage::Lineage::new(StorageKey::Parents),
         }
     }
 
+    /// Upgrade the state of the 2022 contract. Tokens minted so far still need to be counted
+    /// for rarity, and their media hashed and dropped in favour of lazy rendering. Call, as the
+    /// owner and until they are done, `migrate_rarity_backfill`, `migrate_media_hash` and
+    /// `migrate_lazy_media`. `scripts/migrate_2026_10_18.js` deploys and runs all of it.
     #[init(ignore_state)]
-    pub fn migrate_2022_09_07_linkdrop() -> Self {
+    pub fn migrate_2026_10_18() -> Self {
         let old_state: OldContract = env::state_read().expect("failed");
         Self {
             tokens: old_state.tokens,
             metadata: old_state.metadata,
             karma: old_state.karma,
-            linkdrop: linkdrop::Linkdrops::new(StorageKey::LinkdropPending),
+            // Pending drops are kept under the same prefix.
+            linkdrop: linkdrop::Linkdrops::new(
+                StorageKey::LinkdropPending,
+                StorageKey::LinkdropPubKeys,
+            ),
+            seeds: seeds::Seeds::new(StorageKey::TokenSeed, StorageKey::EditionSerial),
+            mint_tickets: tickets::MintTickets::new(StorageKey::MintTicket),
+            rarity: rarity::Rarity::new_with_backfill(
+                StorageKey::RaritySupply,
+                StorageKey::RarityTrait,
+                StorageKey::RarityBucket,
+            ),
+            inscriptions: inscriptions::Inscriptions::new(StorageKey::Inscription),
+            gifts: gifts::Gifts::new(StorageKey::GiftCount),
+            lineage: lineage::Lineage::new(StorageKey::Parents),
+        }
+    }
+
+    /// Count the traits of up to `limit` tokens minted before rarity was tracked. Returns `true`
+    /// once every token is counted.
+    pub fn migrate_rarity_backfill(&mut self, limit: Option<u64>) -> bool {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.tokens.owner_id,
+            "Unauthorized"
+        );
+        let limit = limit.unwrap_or(50) as usize;
+        let token_ids = self.internal_token_ids(self.rarity.backfill_cursor(), limit);
+        for token_id in &token_ids {
+            match self.internal_token_attributes(token_id) {
+                Some((edition, attributes)) => self
+                    .rarity
+                    .backfill(token_id, Some((edition.id, &attributes))),
+                None => self.rarity.backfill(token_id, None),
+            }
+        }
+        if token_ids.len() < limit {
+            self.rarity.finish_backfill();
+            return true;
+        }
+        false
+    }
+
+    /// Drop the stored `media` of tokens minted before lazy rendering, `limit` tokens at a time
+    /// after `from_token_id`. `updated` counts the tokens converted.
+    ///
+    /// Only media that matches what the generator renders from the token's seed is dropped, so
+    /// tokens minted with custom metadata through `nft_mint` are left as they are.
+    pub fn migrate_lazy_media(
+        &mut self,
+        from_token_id: Option<TokenId>,
+        limit: Option<u64>,
+    ) -> Page {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.tokens.owner_id,
+            "Unauthorized"
+        );
+        let limit = limit.unwrap_or(50) as usize;
+        let token_ids = self.internal_token_ids(from_token_id, limit);
+        let token_metadata_by_id = self.tokens.token_metadata_by_id.as_mut().unwrap();
+        let mut converted = 0;
+        for token_id in &token_ids {
+            let mut metadata = match token_metadata_by_id.get(token_id) {
+                Some(metadata) => metadata,
+                None => continue,
+            };
+            let token_seed = match self.seeds.get(token_id) {
+                Some(token_seed) => token_seed,
+                None => continue,
+            };
+            if metadata.media.is_some()
+                && metadata.media
+                    == media::encode(
+                        &render_svg(token_id, &token_seed),
+                        edition::media_encoding(token_id),
+                    )
+                    .media
+            {
+                metadata.media = None;
+                token_metadata_by_id.insert(token_id, &metadata);
+                converted += 1;
+            }
+        }
+        Page::new(converted, &token_ids, limit)
+    }
+
+    /// Store the `media_hash`, or `reference_hash` for reference-encoded editions, of generated
+    /// tokens minted before hashes were recorded, `limit` tokens at a time after
+    /// `from_token_id`.
+    ///
+    /// Media still stored on-chain is hashed as stored, anything else as rendered from the seed.
+    pub fn migrate_media_hash(
+        &mut self,
+        from_token_id: Option<TokenId>,
+        limit: Option<u64>,
+    ) -> Page {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.tokens.owner_id,
+            "Unauthorized"
+        );
+        let limit = limit.unwrap_or(50) as usize;
+        let token_ids = self.internal_token_ids(from_token_id, limit);
+        let token_metadata_by_id = self.tokens.token_metadata_by_id.as_mut().unwrap();
+        let mut updated = 0;
+        for token_id in &token_ids {
+            let mut metadata = match token_metadata_by_id.get(token_id) {
+                Some(metadata) => metadata,
+                None => continue,
+            };
+            if metadata.media_hash.is_some() || metadata.reference_hash.is_some() {
+                continue;
+            }
+            let token_seed = match self.seeds.get(token_id) {
+                Some(token_seed) => token_seed,
+                None => continue,
+            };
+            match (&metadata.media, &metadata.reference) {
+                (Some(uri), _) => match media::data_uri_content(uri) {
+                    Some(content) => metadata.media_hash = Some(media::hash(&content)),
+                    None => continue,
+                },
+                (None, Some(_)) => continue,
+                (None, None) => {
+                    let encoded = media::encode(
+                        &render_svg(token_id, &token_seed),
+                        edition::media_encoding(token_id),
+                    );
+                    metadata.media_hash = encoded.media_hash;
+                    metadata.reference_hash = encoded.reference_hash;
+                }
+            }
+            token_metadata_by_id.insert(token_id, &metadata);
+            updated += 1;
+        }
+        Page::new(updated, &token_ids, limit)
+    }
+
+    /// Ids of up to `limit` tokens after `from_token_id`, from the first token without one.
+    fn internal_token_ids(&self, from_token_id: Option<TokenId>, limit: usize) -> Vec<TokenId> {
+        match from_token_id {
+            Some(from_token_id) => self
+                .tokens
+                .owner_by_id
+                .iter_from(from_token_id)
+                .take(limit)
+                .map(|(token_id, _)| token_id)
+                .collect(),
+            None => self
+                .tokens
+                .owner_by_id
+                .iter()
+                .take(limit)
+                .map(|(token_id, _)| token_id)
+                .collect(),
         }
     }
 
@@ -149,37 +387,309 @@ impl Contract {
     #[payable]
     pub fn nft_mint_2022(&mut self, receiver_id: AccountId) -> Token {
         assert!(
-            env::attached_deposit() >= 1000000000000000000000000,
+            env::attached_deposit() >= MINT_PRICE,
             "In sufficient deposit amount"
         );
-        let seed = near_sdk::env::random_seed();
-        let token_id = format!(
-            "2022-{}",
-            bs58::encode(&seed)
-                .with_alphabet(bs58::Alphabet::BITCOIN)
-                .into_string()
+        let edition = edition::get("2022").unwrap();
+        let token = self.internal_mint_edition(edition, receiver_id, None);
+        NftMint {
+            owner_id: &token.owner_id,
+            token_ids: &[&token.token_id],
+            memo: None,
+        }
+        .emit();
+        token
+    }
+
+    /// Mint one token of `edition` for each of `receivers`, e.g. for gifts and airdrops.
+    ///
+    /// Every token gets its own seed, see `internal_mint_edition`. The batch size is capped by
+    /// `MAX_BATCH_MINT` and by the prepaid gas.
+    #[payable]
+    pub fn nft_batch_mint(&mut self, edition: String, receivers: Vec<AccountId>) -> Vec<Token> {
+        let edition = edition::get(&edition).expect("Unknown edition");
+        let count = receivers.len();
+        assert!(count > 0, "No receivers");
+        assert!(
+            count <= MAX_BATCH_MINT,
+            "Too many receivers, at most {} per batch",
+            MAX_BATCH_MINT
+        );
+        assert!(
+            env::prepaid_gas() - env::used_gas() >= GAS_FOR_MINT * count as u64,
+            "Not enough gas to mint {} tokens",
+            count
+        );
+        assert!(
+            env::attached_deposit() >= MINT_PRICE * count as Balance,
+            "In sufficient deposit amount"
+        );
+
+        let tokens: Vec<Token> = receivers
+            .into_iter()
+            .map(|receiver_id| self.internal_mint_edition(edition, receiver_id, None))
+            .collect();
+
+        let mut token_ids_by_owner: BTreeMap<&AccountId, Vec<&str>> = BTreeMap::new();
+        for token in &tokens {
+            token_ids_by_owner
+                .entry(&token.owner_id)
+                .or_default()
+                .push(&token.token_id);
+        }
+        let mints: Vec<NftMint> = token_ids_by_owner
+            .iter()
+            .map(|(owner_id, token_ids)| NftMint {
+                owner_id,
+                token_ids,
+                memo: None,
+            })
+            .collect();
+        NftMint::emit_many(&mints);
+        tokens
+    }
+
+    /// Pay for a mint of `edition` now and reveal it with `nft_mint_reveal` in a later block.
+    ///
+    /// The seed is derived from the randomness of the reveal block, which is unknown at commit
+    /// time, so the art can not be fished for by simulating or retrying the mint.
+    #[payable]
+    pub fn nft_mint_commit(&mut self, edition: String, receiver_id: AccountId) -> U64 {
+        assert!(edition::get(&edition).is_some(), "Unknown edition");
+        assert!(
+            env::attached_deposit() >= MINT_PRICE,
+            "In sufficient deposit amount"
         );
-        let data_uri = svg_data_uri(crate::facai_gen::new(&seed));
+        self.mint_tickets.commit(&edition, receiver_id).into()
+    }
+
+    /// Turn a mint ticket into its token. Anyone can reveal, the token always goes to the
+    /// receiver given at commit time.
+    pub fn nft_mint_reveal(&mut self, ticket_id: U64) -> Token {
+        let ticket = self.mint_tickets.reveal(ticket_id.into());
+        let edition = edition::get(&ticket.edition).unwrap();
+        let token = self.internal_mint_edition(edition, ticket.receiver_id, None);
+        NftMint {
+            owner_id: &token.owner_id,
+            token_ids: &[&token.token_id],
+            memo: None,
+        }
+        .emit();
+        token
+    }
+
+    pub fn nft_mint_ticket(&self, ticket_id: U64) -> Option<tickets::MintTicket> {
+        self.mint_tickets.get(ticket_id.into())
+    }
+
+    /// Mint a token of `edition` with art generated from a freshly derived seed, mixing in the
+    /// seeds of the `parents` it is baked from, if any. Does not emit the mint event.
+    ///
+    /// The token id is derived from the seed. In the unlikely case it is already taken, a new
+    /// seed is derived instead of aborting the mint.
+    fn internal_mint_edition(
+        &mut self,
+        edition: &edition::Edition,
+        receiver_id: AccountId,
+        parents: Option<&[TokenId; 2]>,
+    ) -> Token {
+        let parent_seeds = parents.map(|parents| {
+            parents
+                .each_ref()
+                .map(|token_id| self.seeds.get(token_id).unwrap().seed)
+        });
+        let (seed, token_id) = loop {
+            let seed = match &parent_seeds {
+                Some(parent_seeds) => self.seeds.next_baked(&receiver_id, parent_seeds),
+                None => self.seeds.next(&receiver_id),
+            };
+            let token_id = match edition.reveal_at {
+                Some(_) => format!("{}-{}", edition.id, self.seeds.next_serial(edition.id)),
+                None => format!(
+                    "{}-{}",
+                    edition.id,
+                    bs58::encode(&seed)
+                        .with_alphabet(bs58::Alphabet::BITCOIN)
+                        .into_string()
+                ),
+            };
+            if self.tokens.owner_by_id.get(&token_id).is_none() {
+                break (seed, token_id);
+            }
+        };
+        let token_seed = seeds::TokenSeed {
+            seed,
+            version: edition.generator,
+        };
+        let attributes = render_attributes(&token_id, &token_seed);
+        if let Some(parents) = parents {
+            self.lineage.insert(&token_id, parents);
+        }
+        // Sealed tokens get their hashes and attributes on reveal, see `nft_reveal`.
+        let encoded = if edition::is_edition_sealed(edition) {
+            None
+        } else {
+            Some(media::encode(
+                &render_svg(&token_id, &token_seed),
+                edition::encoding(edition),
+            ))
+        };
         let token_metadata = TokenMetadata {
-            title: Some("恭喜发财".to_string()),
-            description: Some("Gong Xi Fa Cai. Mooncake NFT 2022 Edition.".to_string()),
-            media: Some(data_uri),
-            media_hash: None,
+            title: Some(edition.title.to_string()),
+            description: Some(edition.description.to_string()),
+            // Rendered from the seed in view calls, see `internal_render_token`.
+            media: None,
+            media_hash: encoded
+                .as_ref()
+                .and_then(|encoded| encoded.media_hash.clone()),
             copies: Some(1),
             issued_at: None,
             expires_at: None,
             starts_at: None,
             updated_at: None,
-            extra: None,
+            extra: encoded
+                .as_ref()
+                .map(|_| self.internal_extra(&token_id, &attributes)),
             reference: None,
-            reference_hash: None,
+            reference_hash: encoded
+                .as_ref()
+                .and_then(|encoded| encoded.reference_hash.clone()),
         };
-        let token = self.tokens.internal_mint_with_refund(
+        self.seeds.insert(&token_id, &token_seed);
+        self.rarity.add(&token_id, edition.id, &attributes);
+        let mut token = self.tokens.internal_mint_with_refund(
             token_id,
             receiver_id,
             Some(token_metadata),
             None,
         );
+        match (encoded, token.metadata.as_mut()) {
+            (Some(encoded), Some(metadata)) => {
+                metadata.media = encoded.media;
+                metadata.reference = encoded.reference;
+                token
+            }
+            _ => self.internal_render_token(token),
+        }
+    }
+
+    /// `extra` of a generated token: its trait attributes, and the tokens it was baked from.
+    fn internal_extra(&self, token_id: &TokenId, attributes: &generator::Attributes) -> String {
+        let mut extra = generator::attributes_document(attributes);
+        if let Some(parents) = self.lineage.parents(token_id) {
+            extra["parents"] = parents.as_slice().into();
+        }
+        extra.to_string()
+    }
+
+    /// Store the hashes and trait attributes of tokens of `edition` minted while it was sealed,
+    /// `limit` tokens at a time after `from_token_id`, and emit a metadata update event for them.
+    /// Anyone can call it once the edition is revealed, the art is rendered from the stored seeds
+    /// either way.
+    pub fn nft_reveal(
+        &mut self,
+        edition: String,
+        from_token_id: Option<TokenId>,
+        limit: Option<u64>,
+    ) -> Page {
+        let edition = edition::get(&edition).expect("Unknown edition");
+        assert!(
+            !edition::is_edition_sealed(edition),
+            "Edition is not revealed yet"
+        );
+        let prefix = format!("{}-", edition.id);
+        let limit = limit.unwrap_or(50) as usize;
+        // Token ids of the edition sort together, right after the prefix.
+        let token_ids: Vec<TokenId> = self
+            .tokens
+            .owner_by_id
+            .iter_from(from_token_id.unwrap_or_else(|| prefix.clone()))
+            .take_while(|(token_id, _)| token_id.starts_with(&prefix))
+            .take(limit)
+            .map(|(token_id, _)| token_id)
+            .collect();
+        let updated_at = (env::block_timestamp() / 1_000_000).to_string();
+        let mut revealed = Vec::new();
+        for token_id in token_ids.iter().cloned() {
+            let token_metadata_by_id = self.tokens.token_metadata_by_id.as_ref().unwrap();
+            let mut metadata = match token_metadata_by_id.get(&token_id) {
+                Some(metadata) => metadata,
+                None => continue,
+            };
+            if metadata.media.is_some()
+                || metadata.reference.is_some()
+                || metadata.media_hash.is_some()
+                || metadata.reference_hash.is_some()
+            {
+                continue;
+            }
+            let token_seed = match self.seeds.get(&token_id) {
+                Some(token_seed) => token_seed,
+                None => continue,
+            };
+            let encoded = media::encode(
+                &self.internal_render_svg(&token_id, &token_seed),
+                edition::encoding(edition),
+            );
+            metadata.media_hash = encoded.media_hash;
+            metadata.reference_hash = encoded.reference_hash;
+            metadata.extra =
+                Some(self.internal_extra(&token_id, &render_attributes(&token_id, &token_seed)));
+            metadata.updated_at = Some(updated_at.clone());
+            let token_metadata_by_id = self.tokens.token_metadata_by_id.as_mut().unwrap();
+            token_metadata_by_id.insert(&token_id, &metadata);
+            revealed.push(token_id);
+        }
+        if !revealed.is_empty() {
+            let token_ids: Vec<&str> = revealed.iter().map(String::as_str).collect();
+            events::NftMetadataUpdate {
+                token_ids: &token_ids,
+                memo: None,
+            }
+            .emit();
+        }
+        Page::new(revealed.len() as u64, &token_ids, limit)
+    }
+
+    /// Bake `token_a` and `token_b`, two generated tokens of the same edition owned by the
+    /// caller, into a new token of that edition. Both are burnt. The seed of the new token is
+    /// derived from theirs and the block seed, and their ids are recorded in the `extra` of its
+    /// metadata and in `nft_ancestry`.
+    ///
+    /// Requires a deposit of exactly 1 yoctoNEAR, like `nft_transfer`.
+    #[payable]
+    pub fn nft_bake(&mut self, token_a: TokenId, token_b: TokenId) -> Token {
+        assert_one_yocto();
+        assert_ne!(token_a, token_b, "Can not bake a token with itself");
+        let owner_id = env::predecessor_account_id();
+        let parents = [token_a, token_b];
+        let [edition_a, edition_b] = parents.each_ref().map(|token_id| {
+            let token_owner_id = self
+                .tokens
+                .owner_by_id
+                .get(token_id)
+                .expect("Token not found");
+            assert_eq!(owner_id, token_owner_id, "Unauthorized");
+            match edition::of_token(token_id) {
+                Some(edition) if self.seeds.get(token_id).is_some() => edition,
+                _ => env::panic_str("Only generated tokens can be baked"),
+            }
+        });
+        assert_eq!(
+            edition_a.id, edition_b.id,
+            "Only tokens of the same edition can be baked"
+        );
+        for token_id in &parents {
+            self.internal_burn(token_id, &owner_id);
+        }
+        NftBurn {
+            owner_id: &owner_id,
+            token_ids: &[&parents[0], &parents[1]],
+            authorized_id: None,
+            memo: None,
+        }
+        .emit();
+        let token = self.internal_mint_edition(edition_a, owner_id, Some(&parents));
         NftMint {
             owner_id: &token.owner_id,
             token_ids: &[&token.token_id],
@@ -189,6 +699,257 @@ impl Contract {
         token
     }
 
+    /// Ancestors `token_id` was baked from, up to `generations` back, by default and at most
+    /// `lineage::MAX_GENERATIONS`. Burnt tokens can be looked up too.
+    pub fn nft_ancestry(
+        &self,
+        token_id: TokenId,
+        generations: Option<u8>,
+    ) -> Vec<lineage::Ancestor> {
+        let generations = generations
+            .unwrap_or(lineage::MAX_GENERATIONS)
+            .min(lineage::MAX_GENERATIONS);
+        self.lineage.ancestry(&token_id, generations)
+    }
+
+    /// Remove `token_id` of `owner_id` with its pending linkdrops, karma quota, inscription and
+    /// gift count, and take it out of the trait counts. The storage of its approvals is refunded
+    /// to the owner. Does not emit the burn event.
+    fn internal_burn(&mut self, token_id: &TokenId, owner_id: &AccountId) {
+        if let Some((edition, attributes)) = self.internal_token_attributes(token_id) {
+            self.rarity.remove(token_id, edition.id, &attributes);
+        }
+        self.linkdrop.remove_token_drops(token_id);
+        self.karma.remove_quota(token_id);
+        self.inscriptions.remove(token_id);
+        self.gifts.remove(token_id);
+        self.tokens.owner_by_id.remove(token_id);
+        if let Some(token_metadata_by_id) = self.tokens.token_metadata_by_id.as_mut() {
+            token_metadata_by_id.remove(token_id);
+        }
+        if let Some(tokens_per_owner) = self.tokens.tokens_per_owner.as_mut() {
+            let mut token_ids = tokens_per_owner.get(owner_id).unwrap();
+            token_ids.remove(token_id);
+            if token_ids.is_empty() {
+                tokens_per_owner.remove(owner_id);
+            } else {
+                tokens_per_owner.insert(owner_id, &token_ids);
+            }
+        }
+        if let Some(next_approval_id_by_id) = self.tokens.next_approval_id_by_id.as_mut() {
+            next_approval_id_by_id.remove(token_id);
+        }
+        if let Some(approvals_by_id) = self.tokens.approvals_by_id.as_mut() {
+            if let Some(approved_account_ids) = approvals_by_id.remove(token_id) {
+                refund_approved_account_ids(owner_id.clone(), &approved_account_ids);
+            }
+        }
+    }
+
+    /// Stamp `inscription` on the art of `token_id`, which only its owner can do, and only once.
+    /// See `mooncake_art::inscriptions::assert_valid` for what an inscription may contain.
+    ///
+    /// The new art is rendered lazily like the art of a fresh token, only its hash is stored.
+    /// Attach a deposit to cover the storage of the inscription, the rest is refunded.
+    #[payable]
+    pub fn nft_inscribe(&mut self, token_id: TokenId, inscription: String) -> Token {
+        let owner_id = self
+            .tokens
+            .owner_by_id
+            .get(&token_id)
+            .expect("Token not found");
+        assert_eq!(env::predecessor_account_id(), owner_id, "Unauthorized");
+        let token_seed = match edition::of_token(&token_id) {
+            Some(_) => self.seeds.get(&token_id),
+            None => None,
+        }
+        .expect("Only generated tokens can be inscribed");
+        let storage_before = env::storage_usage();
+        self.inscriptions.insert(&token_id, &inscription);
+        self.internal_update_art(&token_id, &token_seed);
+        refund_deposit(env::storage_usage().saturating_sub(storage_before));
+        self.nft_token(token_id).unwrap()
+    }
+
+    pub fn nft_inscription(&self, token_id: TokenId) -> Option<String> {
+        self.inscriptions.get(&token_id)
+    }
+
+    /// How often `token_id` was gifted, counting the gifts karma credits. See `gifts::THRESHOLDS`
+    /// for the counts at which its art evolves.
+    pub fn nft_gift_count(&self, token_id: TokenId) -> u32 {
+        self.gifts.count(&token_id)
+    }
+
+    /// Count a gift of `token_id` that karma credited, and update its art if it evolved.
+    fn internal_count_gift(&mut self, token_id: &TokenId) {
+        if !self.gifts.add(token_id) {
+            return;
+        }
+        if edition::of_token(token_id).is_some() {
+            if let Some(token_seed) = self.seeds.get(token_id) {
+                self.internal_update_art(token_id, &token_seed);
+            }
+        }
+    }
+
+    /// Art of a generated token, with what was added to it since it was minted.
+    fn internal_render_svg(&self, token_id: &TokenId, token_seed: &seeds::TokenSeed) -> String {
+        let inscription = self.inscriptions.get(token_id);
+        let extras = generator::Extras {
+            inscription: inscription.as_deref(),
+            evolution: self.gifts.evolution(token_id),
+        };
+        render_svg_with(token_id, token_seed, &extras)
+    }
+
+    /// Store the hashes of the changed art of a generated token, which is rendered lazily from
+    /// then on, bump its `updated_at` and emit a metadata update event.
+    fn internal_update_art(&mut self, token_id: &TokenId, token_seed: &seeds::TokenSeed) {
+        // Hashed on reveal, see `nft_reveal`.
+        if edition::is_sealed(token_id) {
+            return;
+        }
+        let encoded = media::encode(
+            &self.internal_render_svg(token_id, token_seed),
+            edition::media_encoding(token_id),
+        );
+        let token_metadata_by_id = self.tokens.token_metadata_by_id.as_mut().unwrap();
+        let mut metadata = token_metadata_by_id.get(token_id).unwrap();
+        metadata.media = None;
+        metadata.media_hash = encoded.media_hash;
+        metadata.reference = None;
+        metadata.reference_hash = encoded.reference_hash;
+        metadata.updated_at = Some((env::block_timestamp() / 1_000_000).to_string());
+        token_metadata_by_id.insert(token_id, &metadata);
+        events::NftMetadataUpdate {
+            token_ids: &[token_id.as_str()],
+            memo: None,
+        }
+        .emit();
+    }
+
+    /// Fill in the `media` or `reference` of a generated token, which only has its seed stored,
+    /// and the trait attributes in `extra` of tokens minted before traits were recorded. Tokens
+    /// of a sealed edition show the placeholder instead.
+    fn internal_render_token(&self, mut token: Token) -> Token {
+        if let Some(metadata) = token.metadata.as_mut() {
+            let unrendered = metadata.media.is_none() && metadata.reference.is_none();
+            if unrendered && edition::is_sealed(&token.token_id) {
+                let edition = edition::of_token(&token.token_id).unwrap();
+                let encoded = media::encode(&placeholder::svg(edition), edition::encoding(edition));
+                metadata.media = encoded.media;
+                metadata.media_hash = encoded.media_hash;
+                metadata.reference = encoded.reference;
+                metadata.reference_hash = encoded.reference_hash;
+                return token;
+            }
+            if unrendered || metadata.extra.is_none() {
+                if let Some(token_seed) = self.seeds.get(&token.token_id) {
+                    if unrendered {
+                        let encoded = media::encode(
+                            &self.internal_render_svg(&token.token_id, &token_seed),
+                            edition::media_encoding(&token.token_id),
+                        );
+                        metadata.media = encoded.media;
+                        metadata.reference = encoded.reference;
+                        if metadata.media_hash.is_none() && metadata.reference_hash.is_none() {
+                            metadata.media_hash = encoded.media_hash;
+                            metadata.reference_hash = encoded.reference_hash;
+                        }
+                    }
+                    if metadata.extra.is_none() {
+                        metadata.extra = Some(self.internal_extra(
+                            &token.token_id,
+                            &render_attributes(&token.token_id, &token_seed),
+                        ));
+                    }
+                }
+            }
+        }
+        token
+    }
+
+    /// Seed and generator version `token_id` was rendered from. Like the other views of the art,
+    /// `None` until the edition is revealed.
+    ///
+    /// The seed is in the contract state all along, sealing only keeps the art out of wallets and
+    /// marketplaces until the reveal.
+    pub fn nft_seed(&self, token_id: TokenId) -> Option<seeds::TokenSeedView> {
+        self.tokens.owner_by_id.get(&token_id)?;
+        if edition::is_sealed(&token_id) {
+            return None;
+        }
+        self.seeds.get(&token_id).map(Into::into)
+    }
+
+    /// Re-run the art generator for `token_id`, so anyone can check that the stored media
+    /// matches the algorithm.
+    pub fn nft_render_svg(&self, token_id: TokenId) -> Option<String> {
+        self.tokens.owner_by_id.get(&token_id)?;
+        if edition::is_sealed(&token_id) {
+            return None;
+        }
+        self.seeds
+            .get(&token_id)
+            .map(|token_seed| self.internal_render_svg(&token_id, &token_seed))
+    }
+
+    /// Size of the metadata of `token_id` with each media encoding, to compare their storage
+    /// cost.
+    pub fn nft_media_sizes(&self, token_id: TokenId) -> Option<Vec<media::MediaSize>> {
+        if edition::is_sealed(&token_id) {
+            return None;
+        }
+        let token = self.nft_token(token_id)?;
+        let token_seed = self.seeds.get(&token.token_id)?;
+        let svg = self.internal_render_svg(&token.token_id, &token_seed);
+        let metadata = token.metadata?;
+        Some(
+            media::ENCODINGS
+                .iter()
+                .map(|&encoding| {
+                    let encoded = media::encode(&svg, encoding);
+                    let metadata = TokenMetadata {
+                        media: encoded.media,
+                        reference: encoded.reference,
+                        ..metadata.clone()
+                    };
+                    media::MediaSize {
+                        encoding,
+                        bytes: metadata.try_to_vec().unwrap().len() as u64,
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Rarity score of `token_id` and its rank within its edition, from the trait distribution
+    /// of all minted tokens of the edition.
+    pub fn nft_rarity(&self, token_id: TokenId) -> Option<rarity::RarityView> {
+        self.tokens.owner_by_id.get(&token_id)?;
+        if edition::is_sealed(&token_id) {
+            return None;
+        }
+        let (edition, attributes) = self.internal_token_attributes(&token_id)?;
+        let score = self.rarity.score(edition.id, &attributes);
+        Some(rarity::RarityView {
+            score,
+            rank: self.rarity.rank(edition.id, score),
+            supply: self.rarity.supply(edition.id),
+        })
+    }
+
+    /// Edition and trait attributes of a token with generated art.
+    fn internal_token_attributes(
+        &self,
+        token_id: &TokenId,
+    ) -> Option<(&'static edition::Edition, generator::Attributes)> {
+        let edition = edition::of_token(token_id)?;
+        let token_seed = self.seeds.get(token_id)?;
+        Some((edition, render_attributes(token_id, &token_seed)))
+    }
+
     pub fn top_rank(&self) -> &Vec<(Balance, AccountId)> {
         self.karma.rank()
     }
@@ -199,7 +960,7 @@ impl Contract {
         assert_eq!(env::predecessor_account_id(), owner_id, "Unauthorized");
         self.tokens
             .nft_approve(token_id.clone(), env::current_account_id(), None);
-        let approval_id: u64 = self
+        let approval_id: u64 = *self
             .tokens
             .approvals_by_id
             .as_ref()
@@ -207,10 +968,8 @@ impl Contract {
             .get(&token_id)
             .unwrap()
             .get(&env::current_account_id())
-            .unwrap()
-            .clone();
-        self.linkdrop
-            .add_drop(&pub_key, &token_id, approval_id.clone());
+            .unwrap();
+        self.linkdrop.add_drop(&pub_key, &token_id, approval_id);
     }
 
     #[payable]
@@ -242,7 +1001,9 @@ impl NonFungibleTokenCore for Contract {
         memo: Option<String>,
     ) {
         let sender_id = self.tokens.owner_by_id.get(&token_id).unwrap();
-        self.karma.increase(&sender_id, &token_id);
+        if self.karma.increase(&sender_id, &token_id) {
+            self.internal_count_gift(&token_id);
+        }
         self.tokens
             .nft_transfer(receiver_id, token_id, approval_id, memo)
     }
@@ -257,13 +1018,17 @@ impl NonFungibleTokenCore for Contract {
         msg: String,
     ) -> PromiseOrValue<bool> {
         let sender_id = self.tokens.owner_by_id.get(&token_id).unwrap();
-        self.karma.increase(&sender_id, &token_id);
+        if self.karma.increase(&sender_id, &token_id) {
+            self.internal_count_gift(&token_id);
+        }
         self.tokens
             .nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
     }
 
     fn nft_token(&self, token_id: TokenId) -> Option<Token> {
-        self.tokens.nft_token(token_id)
+        self.tokens
+            .nft_token(token_id)
+            .map(|token| self.internal_render_token(token))
     }
 }
 
@@ -286,28 +1051,61 @@ impl NonFungibleTokenResolver for Contract {
     }
 }
 
-fn svg_data_uri(svg: String) -> String {
-    let encoded = svg
-        .replace("%", "%25")
-        .replace("> <", "><") // normalise spaces elements
-        .replace("; }", ";}") // normalise spaces css
-        .replace("<", "%3c")
-        .replace(">", "%3e")
-        .replace("\"", "'")
-        .replace("#", "%23") // needed for ie and firefox
-        .replace("{", "%7b")
-        .replace("}", "%7d")
-        .replace("|", "%7c")
-        .replace("^", "%5e")
-        .replace("`", "%60")
-        .replace("@", "%40");
-
-    format!("{}{}", "data:image/svg+xml;charset=UTF-8,", &encoded)
+fn render_svg(token_id: &str, token_seed: &seeds::TokenSeed) -> String {
+    generator::by_version(token_seed.version).svg(&token_seed.art_seed(), edition::style(token_id))
+}
+
+fn render_svg_with(
+    token_id: &str,
+    token_seed: &seeds::TokenSeed,
+    extras: &generator::Extras,
+) -> String {
+    generator::by_version(token_seed.version).svg_with(
+        &token_seed.art_seed(),
+        edition::style(token_id),
+        extras,
+    )
+}
+
+fn render_attributes(token_id: &str, token_seed: &seeds::TokenSeed) -> generator::Attributes {
+    generator::by_version(token_seed.version)
+        .attributes(&token_seed.art_seed(), edition::style(token_id))
 }
 
 // near_contract_standards::impl_non_fungible_token_core!(Contract, tokens);
 near_contract_standards::impl_non_fungible_token_approval!(Contract, tokens);
-near_contract_standards::impl_non_fungible_token_enumeration!(Contract, tokens);
+
+#[near_bindgen]
+impl NonFungibleTokenEnumeration for Contract {
+    fn nft_total_supply(&self) -> U128 {
+        self.tokens.nft_total_supply()
+    }
+
+    fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.tokens
+            .nft_tokens(from_index, limit)
+            .into_iter()
+            .map(|token| self.internal_render_token(token))
+            .collect()
+    }
+
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        self.tokens.nft_supply_for_owner(account_id)
+    }
+
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        self.tokens
+            .nft_tokens_for_owner(account_id, from_index, limit)
+            .into_iter()
+            .map(|token| self.internal_render_token(token))
+            .collect()
+    }
+}
 
 #[near_bindgen]
 impl NonFungibleTokenMetadataProvider for Contract {
@@ -334,11 +1132,16 @@ mod tests {
         builder
     }
 
+    /// Media of `svg` as the edition of `token_id` stores it.
+    fn edition_media(token_id: &str, svg: &str) -> media::Media {
+        media::encode(svg, edition::media_encoding(token_id))
+    }
+
     #[test]
     fn test_mint() {
         let mut context = get_context(accounts(0));
         testing_env!(context.build());
-        let mut contract = Contract::new_default_meta(accounts(1).into());
+        let mut contract = Contract::new_default_meta(accounts(1));
 
         testing_env!(context
             .storage_usage(env::storage_usage())
@@ -349,39 +1152,854 @@ mod tests {
         let token = contract.nft_mint_2022(accounts(0));
         assert!(token.token_id.starts_with("2022-"));
         assert_eq!(token.owner_id.to_string(), accounts(0).to_string());
+        assert!(contract.seeds.get(&token.token_id).is_some());
+        let extra = token.metadata.unwrap().extra.unwrap();
+        assert!(extra.starts_with(r#"{"attributes":["#));
     }
 
     #[test]
-    fn test_transfer() {
+    fn test_mint_same_block() {
         let mut context = get_context(accounts(0));
         testing_env!(context.build());
-        let mut contract = Contract::new_default_meta(accounts(0).into());
+        let mut contract = Contract::new_default_meta(accounts(1));
 
         testing_env!(context
             .storage_usage(env::storage_usage())
             .attached_deposit(MINT_COST)
             .predecessor_account_id(accounts(0))
             .build());
-        let token = contract.nft_mint_2022(accounts(0));
+
+        let token_a = contract.nft_mint_2022(accounts(0));
+        let token_b = contract.nft_mint_2022(accounts(0));
+        assert_ne!(token_a.token_id, token_b.token_id);
+    }
+
+    #[test]
+    fn test_batch_mint() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
 
         testing_env!(context
             .storage_usage(env::storage_usage())
-            .attached_deposit(1)
+            .attached_deposit(MINT_COST * 3)
             .predecessor_account_id(accounts(0))
             .build());
-        contract.nft_transfer(accounts(1), token.token_id.clone(), None, None);
+        let tokens = contract.nft_batch_mint(
+            "2022".to_string(),
+            vec![accounts(1), accounts(2), accounts(1)],
+        );
+        assert_eq!(tokens.len(), 3);
+        assert_ne!(tokens[0].token_id, tokens[1].token_id);
+        assert_ne!(tokens[0].token_id, tokens[2].token_id);
+        assert_ne!(tokens[1].token_id, tokens[2].token_id);
+        assert_eq!(tokens[2].owner_id.to_string(), accounts(1).to_string());
+    }
+
+    #[test]
+    fn test_batch_mint_gas() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        for edition in ["2022", "2027"] {
+            testing_env!(context
+                .storage_usage(env::storage_usage())
+                .attached_deposit(MINT_COST * MAX_BATCH_MINT as u128)
+                .prepaid_gas(Gas(300_000_000_000_000))
+                .predecessor_account_id(accounts(0))
+                .build());
+            let before = env::used_gas();
+            contract.nft_batch_mint(edition.to_string(), vec![accounts(1); MAX_BATCH_MINT]);
+            let per_token = (env::used_gas() - before) / MAX_BATCH_MINT as u64;
+            assert!(
+                per_token <= GAS_FOR_MINT * 2 / 3,
+                "{} mints burn {:?} each",
+                edition,
+                per_token
+            );
+        }
+        assert!(GAS_FOR_MINT * MAX_BATCH_MINT as u64 <= Gas(300_000_000_000_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "In sufficient deposit amount")]
+    fn test_batch_mint_insufficient_deposit() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .attached_deposit(MINT_COST)
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.nft_batch_mint("2022".to_string(), vec![accounts(1), accounts(2)]);
+    }
+
+    #[test]
+    fn test_mint_commit_reveal() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .block_index(1)
+            .build());
+        let ticket_id = contract.nft_mint_commit("2022".to_string(), accounts(1));
 
         testing_env!(context
             .storage_usage(env::storage_usage())
-            .account_balance(env::account_balance())
-            .is_view(true)
             .attached_deposit(0)
+            .block_index(2)
+            .random_seed([7; 32])
+            .predecessor_account_id(accounts(2))
             .build());
-        if let Some(token) = contract.nft_token(token.token_id.clone()) {
-            assert_eq!(token.token_id, token.token_id);
-            assert_eq!(token.owner_id.to_string(), accounts(1).to_string());
-        } else {
-            panic!("token not correctly created, or not found by nft_token");
-        }
+        let token = contract.nft_mint_reveal(ticket_id);
+        assert!(token.token_id.starts_with("2022-"));
+        assert_eq!(token.owner_id.to_string(), accounts(1).to_string());
+        assert!(contract.nft_mint_ticket(ticket_id).is_none());
+    }
+
+    #[test]
+    fn test_rarity() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST * 4)
+            .build());
+        let tokens = contract.nft_batch_mint(
+            "2022".to_string(),
+            vec![accounts(1), accounts(1), accounts(2), accounts(3)],
+        );
+
+        let mut ranks: Vec<u64> = tokens
+            .iter()
+            .map(|token| {
+                let rarity = contract.nft_rarity(token.token_id.clone()).unwrap();
+                assert_eq!(rarity.supply, 4);
+                assert!(rarity.score >= 5 * 100);
+                rarity.rank
+            })
+            .collect();
+        ranks.sort();
+        assert_eq!(ranks[0], 1);
+        assert!(ranks[3] <= 4);
+        assert!(contract.nft_rarity("custom".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let token = contract.nft_mint_2022(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.nft_transfer(accounts(1), token.token_id.clone(), None, None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        if let Some(token) = contract.nft_token(token.token_id.clone()) {
+            assert_eq!(token.token_id, token.token_id);
+            assert_eq!(token.owner_id.to_string(), accounts(1).to_string());
+        } else {
+            panic!("token not correctly created, or not found by nft_token");
+        }
+    }
+
+    #[test]
+    fn test_lazy_media() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        let token = contract.nft_mint_2022(accounts(1));
+        let stored = contract
+            .tokens
+            .token_metadata_by_id
+            .as_ref()
+            .unwrap()
+            .get(&token.token_id)
+            .unwrap();
+        assert!(stored.media.is_none());
+
+        let media = token.metadata.unwrap().media;
+        assert!(media.is_some());
+        let token = contract.nft_token(token.token_id).unwrap();
+        assert_eq!(token.metadata.unwrap().media, media);
+        let tokens = contract.nft_tokens_for_owner(accounts(1), None, None);
+        assert_eq!(tokens[0].metadata.as_ref().unwrap().media, media);
+    }
+
+    #[test]
+    fn test_migrate_lazy_media() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        let legacy_token_id = |seed: [u8; 32]| {
+            format!(
+                "2022-{}",
+                bs58::encode(seed)
+                    .with_alphabet(bs58::Alphabet::BITCOIN)
+                    .into_string()
+            )
+        };
+        // As minted by the 2022 contract.
+        let media = include_str!("../tests/golden/2022-media-0000000000000000.txt").to_string();
+        let metadata = TokenMetadata {
+            title: Some("恭喜发财".to_string()),
+            description: None,
+            media: Some(media.clone()),
+            media_hash: None,
+            copies: Some(1),
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        };
+        // Renders the same art, but the media isn't the URI the contract would store.
+        let reencoded_id = legacy_token_id([0xffu8; 32]);
+        let reencoded_media = svg::data_uri(&generator::by_version(1).svg(
+            &generator::Seed::new([0xffu8; 32]),
+            &generator::Style::CLASSIC,
+        ));
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        contract.nft_mint(legacy_token_id([0u8; 32]), accounts(1), metadata.clone());
+        contract.nft_mint(
+            reencoded_id.clone(),
+            accounts(1),
+            TokenMetadata {
+                media: Some(reencoded_media.clone()),
+                ..metadata.clone()
+            },
+        );
+        contract.nft_mint(
+            "custom".to_string(),
+            accounts(1),
+            TokenMetadata {
+                media: Some("ipfs://custom".to_string()),
+                ..metadata
+            },
+        );
+
+        testing_env!(context.storage_usage(env::storage_usage()).build());
+        let storage_before = env::storage_usage();
+        assert_eq!(
+            contract.migrate_lazy_media(None, Some(2)),
+            Page {
+                updated: 1,
+                last_token_id: Some(reencoded_id.clone()),
+            }
+        );
+        assert!(env::storage_usage() < storage_before);
+        assert_eq!(
+            contract.migrate_lazy_media(Some(reencoded_id.clone()), Some(2)),
+            Page {
+                updated: 0,
+                last_token_id: None,
+            }
+        );
+
+        let token = contract.nft_token(legacy_token_id([0u8; 32])).unwrap();
+        assert_eq!(token.metadata.unwrap().media, Some(media));
+        let token = contract.nft_token(reencoded_id).unwrap();
+        assert_eq!(token.metadata.unwrap().media, Some(reencoded_media));
+        let token = contract.nft_token("custom".to_string()).unwrap();
+        assert_eq!(
+            token.metadata.unwrap().media,
+            Some("ipfs://custom".to_string())
+        );
+    }
+
+    #[test]
+    fn test_migrate_2026_10_18() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut old_state = OldContract {
+            tokens: NonFungibleToken::new(
+                StorageKey::NonFungibleToken,
+                accounts(0),
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                Some(StorageKey::Approval),
+            ),
+            metadata: LazyOption::new(
+                StorageKey::Metadata,
+                Some(&NFTContractMetadata {
+                    spec: NFT_METADATA_SPEC.to_string(),
+                    name: "Mooncake".to_string(),
+                    symbol: "MOONCAKE".to_string(),
+                    icon: None,
+                    base_uri: None,
+                    reference: None,
+                    reference_hash: None,
+                }),
+            ),
+            karma: karma::Karma::new(StorageKey::Karma, StorageKey::KarmaQuota),
+            linkdrop: LookupMap::new(StorageKey::LinkdropPending),
+        };
+        // Minted by the 2022 contract.
+        let minted = [
+            (
+                [0u8; 32],
+                include_str!("../tests/golden/2022-media-0000000000000000.txt"),
+            ),
+            (
+                [0xffu8; 32],
+                include_str!("../tests/golden/2022-media-ffffffffffffffff.txt"),
+            ),
+        ]
+        .map(|(seed, media)| {
+            let token_id = format!(
+                "2022-{}",
+                bs58::encode(seed)
+                    .with_alphabet(bs58::Alphabet::BITCOIN)
+                    .into_string()
+            );
+            let metadata = TokenMetadata {
+                title: Some("恭喜发财".to_string()),
+                description: Some("Gong Xi Fa Cai. Mooncake NFT 2022 Edition.".to_string()),
+                media: Some(media.to_string()),
+                media_hash: None,
+                copies: Some(1),
+                issued_at: None,
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: None,
+                reference: None,
+                reference_hash: None,
+            };
+            old_state.tokens.internal_mint_with_refund(
+                token_id.clone(),
+                accounts(1),
+                Some(metadata),
+                None,
+            );
+            (token_id, media)
+        });
+        env::state_write(&old_state);
+
+        let mut contract = Contract::migrate_2026_10_18();
+        assert!(contract.migrate_rarity_backfill(None));
+        assert_eq!(contract.rarity.supply("2022"), 2);
+        assert_eq!(contract.migrate_media_hash(None, None).updated, 2);
+        assert_eq!(contract.migrate_lazy_media(None, None).updated, 2);
+        for (token_id, media) in &minted {
+            let metadata = contract
+                .nft_token(token_id.clone())
+                .unwrap()
+                .metadata
+                .unwrap();
+            assert_eq!(metadata.media.as_deref(), Some(*media));
+            assert_eq!(
+                metadata.media_hash,
+                Some(media::hash(&media::data_uri_content(media).unwrap()))
+            );
+            assert!(contract.nft_rarity(token_id.clone()).is_some());
+        }
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        contract.nft_mint_2022(accounts(2));
+        assert_eq!(contract.nft_total_supply(), U128(3));
+        assert_eq!(contract.rarity.supply("2022"), 3);
+    }
+
+    #[test]
+    fn test_migrate_media_hash() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        let legacy_token = |seed: &generator::Seed| {
+            let token_id = format!(
+                "2022-{}",
+                bs58::encode(&**seed)
+                    .with_alphabet(bs58::Alphabet::BITCOIN)
+                    .into_string()
+            );
+            let encoded = edition_media(
+                &token_id,
+                &generator::by_version(1).svg(seed, &generator::Style::CLASSIC),
+            );
+            let metadata = TokenMetadata {
+                title: Some("恭喜发财".to_string()),
+                description: None,
+                media: encoded.media,
+                media_hash: None,
+                copies: Some(1),
+                issued_at: None,
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: None,
+                reference: None,
+                reference_hash: None,
+            };
+            (token_id, metadata, encoded.media_hash.unwrap())
+        };
+        let (lazy_id, lazy_metadata, lazy_hash) = legacy_token(&generator::Seed::new([3u8; 32]));
+        let (stored_id, stored_metadata, stored_hash) =
+            legacy_token(&generator::Seed::new([4u8; 32]));
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        contract.nft_mint(lazy_id.clone(), accounts(1), lazy_metadata);
+        assert_eq!(contract.migrate_lazy_media(None, None).updated, 1);
+        contract.nft_mint(stored_id.clone(), accounts(1), stored_metadata.clone());
+        contract.nft_mint(
+            "custom".to_string(),
+            accounts(1),
+            TokenMetadata {
+                media: Some("ipfs://custom".to_string()),
+                ..stored_metadata
+            },
+        );
+
+        testing_env!(context.storage_usage(env::storage_usage()).build());
+        let page = contract.migrate_media_hash(None, Some(1));
+        assert_eq!(page.updated, 1);
+        let page = contract.migrate_media_hash(page.last_token_id, Some(3));
+        assert_eq!(
+            page,
+            Page {
+                updated: 1,
+                last_token_id: None,
+            }
+        );
+        assert_eq!(contract.migrate_media_hash(None, None).updated, 0);
+        let token_metadata_by_id = contract.tokens.token_metadata_by_id.as_ref().unwrap();
+        let media_hash = |token_id: &str| {
+            token_metadata_by_id
+                .get(&token_id.to_string())
+                .unwrap()
+                .media_hash
+        };
+        assert_eq!(media_hash(&lazy_id), Some(lazy_hash));
+        assert_eq!(media_hash(&stored_id), Some(stored_hash));
+        assert_eq!(media_hash("custom"), None);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        let token = contract.nft_mint_2022(accounts(1));
+
+        let svg = contract.nft_render_svg(token.token_id.clone()).unwrap();
+        let metadata = token.metadata.unwrap();
+        let encoded = edition_media(&token.token_id, &svg);
+        assert_eq!(metadata.media_hash, encoded.media_hash);
+        assert_eq!(metadata.media, encoded.media);
+        let token_seed = contract.nft_seed(token.token_id).unwrap();
+        assert_eq!(token_seed.version, 1);
+        assert_eq!(token_seed.generator, "facai v1");
+        assert!(contract.nft_seed("2022-unknown".to_string()).is_none());
+    }
+
+    /// The browser preview of a seed, without a generator version, is the art of a freshly
+    /// minted token.
+    #[test]
+    fn test_preview_render() {
+        let edition = edition::latest();
+        let mut context = get_context(accounts(0));
+        testing_env!(context
+            .block_timestamp(edition.reveal_at.unwrap_or_default())
+            .build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        for _ in 0..3 {
+            testing_env!(context
+                .storage_usage(env::storage_usage())
+                .attached_deposit(MINT_COST)
+                .random_seed([contract.nft_total_supply().0 as u8; 32])
+                .build());
+            let token = contract.internal_mint_edition(edition, accounts(1), None);
+            let token_seed = contract.nft_seed(token.token_id.clone()).unwrap();
+            let seed = bs58::decode(token_seed.seed)
+                .with_alphabet(bs58::Alphabet::BITCOIN)
+                .into_vec()
+                .unwrap();
+            let preview = mooncake_preview::render(&seed, None).ok().unwrap();
+            assert!(Some(preview) == contract.nft_render_svg(token.token_id));
+        }
+    }
+
+    /// Media of 2022 tokens as the contract minted them, before art was rendered on demand.
+    #[test]
+    fn test_legacy_media() {
+        let minted = [
+            (
+                [0u8; 32],
+                include_str!("../tests/golden/2022-media-0000000000000000.txt"),
+            ),
+            (
+                [0xffu8; 32],
+                include_str!("../tests/golden/2022-media-ffffffffffffffff.txt"),
+            ),
+        ];
+        let encoding = edition::encoding(edition::get("2022").unwrap());
+        for (seed, media) in minted {
+            let svg = generator::by_version(1)
+                .svg(&generator::Seed::new(seed), &generator::Style::CLASSIC);
+            assert!(media::encode(&svg, encoding).media.unwrap() == media);
+        }
+    }
+
+    #[test]
+    fn test_inscribe() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        let token = contract.nft_mint_2022(accounts(1));
+        let plain_svg = contract.nft_render_svg(token.token_id.clone()).unwrap();
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST / 100)
+            .block_timestamp(1_664_000_000_000_000_000)
+            .predecessor_account_id(accounts(1))
+            .build());
+        let token = contract.nft_inscribe(token.token_id, "中秋快乐".to_string());
+        let svg = contract.nft_render_svg(token.token_id.clone()).unwrap();
+        assert_ne!(svg, plain_svg);
+        assert!(svg.contains(">中秋快乐</text>"));
+        let metadata = token.metadata.unwrap();
+        let encoded = edition_media(&token.token_id, &svg);
+        assert_eq!(metadata.media, encoded.media);
+        assert_eq!(metadata.media_hash, encoded.media_hash);
+        assert_eq!(metadata.updated_at, Some("1664000000000".to_string()));
+        assert_eq!(
+            contract.nft_inscription(token.token_id.clone()),
+            Some("中秋快乐".to_string())
+        );
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            &format!(
+                r#"EVENT_JSON:{{"data":[{{"token_ids":["{}"]}}],"event":"nft_metadata_update","standard":"nep171","version":"1.0.0"}}"#,
+                token.token_id
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_inscribe_not_owner() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        let token = contract.nft_mint_2022(accounts(1));
+        contract.nft_inscribe(token.token_id, "福".to_string());
+    }
+
+    #[test]
+    fn test_gift_evolution() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        let token_id = contract.nft_mint_2022(accounts(1)).token_id;
+        let minted_svg = contract.nft_render_svg(token_id.clone()).unwrap();
+
+        for (gift, (from, to)) in [(1, 2), (2, 1)].into_iter().enumerate() {
+            testing_env!(context
+                .storage_usage(env::storage_usage())
+                .attached_deposit(1)
+                .block_timestamp(1_664_000_000_000_000_000 + gift as u64)
+                .predecessor_account_id(accounts(from))
+                .build());
+            contract.nft_transfer(accounts(to), token_id.clone(), None, None);
+        }
+        assert_eq!(contract.nft_gift_count(token_id.clone()), 2);
+        let svg = contract.nft_render_svg(token_id.clone()).unwrap();
+        assert_ne!(svg, minted_svg);
+        assert!(svg.contains("<circle cx=\"548\""));
+        let metadata = contract
+            .nft_token(token_id.clone())
+            .unwrap()
+            .metadata
+            .unwrap();
+        let encoded = edition_media(&token_id, &svg);
+        assert_eq!(metadata.media, encoded.media);
+        assert_eq!(metadata.media_hash, encoded.media_hash);
+        assert_eq!(metadata.updated_at, Some("1664000000000".to_string()));
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"nft_metadata_update""#)));
+    }
+
+    #[test]
+    fn test_bake() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST * 3)
+            .build());
+        let tokens = contract.nft_batch_mint(
+            "2022".to_string(),
+            vec![accounts(1), accounts(1), accounts(1)],
+        );
+        let parents = [tokens[0].token_id.clone(), tokens[1].token_id.clone()];
+        contract.inscriptions.insert(&parents[0], "福");
+        contract.gifts.add(&parents[1]);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(1))
+            .build());
+        let child = contract.nft_bake(parents[0].clone(), parents[1].clone());
+        assert!(child.token_id.starts_with("2022-"));
+        assert_eq!(child.owner_id.to_string(), accounts(1).to_string());
+        assert!(parents
+            .iter()
+            .all(|token_id| contract.nft_token(token_id.clone()).is_none()));
+        assert_eq!(contract.nft_total_supply(), U128(2));
+        assert_eq!(contract.nft_supply_for_owner(accounts(1)), U128(2));
+        assert_eq!(contract.rarity.supply("2022"), 2);
+        assert!(contract.nft_inscription(parents[0].clone()).is_none());
+        assert_eq!(contract.nft_gift_count(parents[1].clone()), 0);
+        let extra = child.metadata.unwrap().extra.unwrap();
+        assert!(extra.starts_with(r#"{"attributes":["#));
+        assert!(extra.ends_with(&format!(
+            r#""parents":["{}","{}"]}}"#,
+            parents[0], parents[1]
+        )));
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[logs.len() - 2].contains(r#""event":"nft_burn""#));
+        assert!(logs[logs.len() - 1].contains(r#""event":"nft_mint""#));
+
+        let grandchild = contract.nft_bake(child.token_id.clone(), tokens[2].token_id.clone());
+        let ancestry = contract.nft_ancestry(grandchild.token_id, None);
+        let summary: Vec<(&str, u8)> = ancestry
+            .iter()
+            .map(|ancestor| (ancestor.token_id.as_str(), ancestor.generation))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (child.token_id.as_str(), 1),
+                (tokens[2].token_id.as_str(), 1),
+                (parents[0].as_str(), 2),
+                (parents[1].as_str(), 2),
+            ]
+        );
+        assert_eq!(ancestry[0].parents, Some(parents));
+        assert_eq!(contract.nft_total_supply(), U128(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_bake_not_owner() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST * 2)
+            .build());
+        let tokens = contract.nft_batch_mint("2022".to_string(), vec![accounts(1), accounts(2)]);
+
+        testing_env!(context
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.nft_bake(tokens[0].token_id.clone(), tokens[1].token_id.clone());
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_bake_without_deposit() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST * 2)
+            .build());
+        let tokens = contract.nft_batch_mint("2022".to_string(), vec![accounts(1), accounts(1)]);
+
+        testing_env!(context
+            .attached_deposit(0)
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.nft_bake(tokens[0].token_id.clone(), tokens[1].token_id.clone());
+    }
+
+    #[test]
+    fn test_sealed_reveal() {
+        let reveal_at = edition::get("2027").unwrap().reveal_at.unwrap();
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST * 2)
+            .block_timestamp(reveal_at - 1)
+            .build());
+        let tokens = contract.nft_batch_mint("2027".to_string(), vec![accounts(1), accounts(2)]);
+        let placeholder = placeholder::svg(edition::get("2027").unwrap());
+        let metadata = tokens[0].metadata.clone().unwrap();
+        let encoded = edition_media(&tokens[0].token_id, &placeholder);
+        assert_eq!(metadata.media, encoded.media);
+        assert_eq!(metadata.media_hash, encoded.media_hash);
+        assert!(metadata.extra.is_none());
+        let token_id = tokens[0].token_id.clone();
+        // Numbered, the ids tell nothing about the seeds.
+        assert_eq!(token_id, "2027-1");
+        assert_eq!(tokens[1].token_id, "2027-2");
+        assert!(preview::token_seed(&token_id).is_none());
+        assert!(contract.nft_seed(token_id.clone()).is_none());
+        assert!(contract.nft_render_svg(token_id.clone()).is_none());
+        assert!(contract.nft_rarity(token_id.clone()).is_none());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .block_timestamp(reveal_at)
+            .predecessor_account_id(accounts(3))
+            .build());
+        let svg = contract.nft_render_svg(token_id.clone()).unwrap();
+        let metadata = contract
+            .nft_token(token_id.clone())
+            .unwrap()
+            .metadata
+            .unwrap();
+        let encoded = edition_media(&token_id, &svg);
+        assert_eq!(metadata.media, encoded.media);
+        assert_eq!(metadata.media_hash, encoded.media_hash);
+        assert!(metadata.extra.unwrap().starts_with(r#"{"attributes":["#));
+
+        let page = contract.nft_reveal("2027".to_string(), None, Some(1));
+        assert_eq!(
+            page,
+            Page {
+                updated: 1,
+                last_token_id: Some(token_id.clone()),
+            }
+        );
+        assert_eq!(
+            contract.nft_reveal("2027".to_string(), page.last_token_id, None),
+            Page {
+                updated: 1,
+                last_token_id: None,
+            }
+        );
+        let stored = contract
+            .tokens
+            .token_metadata_by_id
+            .as_ref()
+            .unwrap()
+            .get(&token_id)
+            .unwrap();
+        assert!(stored.media.is_none());
+        assert_eq!(stored.media_hash, encoded.media_hash);
+        assert!(stored.extra.is_some());
+        assert_eq!(stored.updated_at, Some("1820937600000".to_string()));
+        let log = near_sdk::test_utils::get_logs().pop().unwrap();
+        assert!(log.contains(r#""event":"nft_metadata_update""#));
+        assert!(log.contains(&tokens[1].token_id));
+        assert_eq!(
+            contract.nft_reveal("2027".to_string(), None, None).updated,
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Edition is not revealed yet")]
+    fn test_reveal_sealed() {
+        let reveal_at = edition::get("2027").unwrap().reveal_at.unwrap();
+        let mut context = get_context(accounts(0));
+        testing_env!(context.block_timestamp(reveal_at - 1).build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+        contract.nft_reveal("2027".to_string(), None, None);
+    }
+
+    #[test]
+    fn test_media_sizes() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_COST)
+            .build());
+        let token = contract.nft_mint_2022(accounts(1));
+
+        let sizes = contract.nft_media_sizes(token.token_id.clone()).unwrap();
+        assert_eq!(
+            sizes.iter().map(|size| size.encoding).collect::<Vec<_>>(),
+            media::ENCODINGS
+        );
+        // The 2022 edition is percent-encoded as it was minted.
+        assert_eq!(
+            sizes[1].bytes,
+            token.metadata.unwrap().try_to_vec().unwrap().len() as u64
+        );
+        assert!(sizes.iter().all(|size| size.bytes > 0));
+        assert!(contract
+            .nft_media_sizes("2022-unknown".to_string())
+            .is_none());
     }
 }
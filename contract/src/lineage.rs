@@ -0,0 +1,107 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::Serialize;
+use near_sdk::IntoStorageKey;
+
+/// Generations `ancestry` walks back at most, 2^n tokens can be in the nth.
+pub const MAX_GENERATIONS: u8 = 5;
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Ancestor {
+    pub token_id: TokenId,
+    /// 1 for the parents, 2 for the grandparents and so on.
+    pub generation: u8,
+    /// The tokens it was baked from, `None` for a minted token.
+    pub parents: Option<[TokenId; 2]>,
+}
+
+/// The two tokens every baked token was baked from. Parents are burnt by baking, their entries
+/// are kept so the ancestry can still be walked through them.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Lineage {
+    parents_by_token_id: LookupMap<TokenId, (TokenId, TokenId)>,
+}
+
+impl Lineage {
+    pub fn new<Q>(parents_by_token_id_prefix: Q) -> Self
+    where
+        Q: IntoStorageKey,
+    {
+        Self {
+            parents_by_token_id: LookupMap::new(parents_by_token_id_prefix),
+        }
+    }
+
+    pub fn insert(&mut self, token_id: &TokenId, parents: &[TokenId; 2]) {
+        let [a, b] = parents.clone();
+        self.parents_by_token_id.insert(token_id, &(a, b));
+    }
+
+    pub fn parents(&self, token_id: &TokenId) -> Option<[TokenId; 2]> {
+        self.parents_by_token_id.get(token_id).map(|(a, b)| [a, b])
+    }
+
+    /// Ancestors of `token_id` up to `generations` back, generation by generation.
+    pub fn ancestry(&self, token_id: &TokenId, generations: u8) -> Vec<Ancestor> {
+        let mut ancestors = Vec::new();
+        let mut children = vec![token_id.clone()];
+        for generation in 1..=generations {
+            let token_ids: Vec<TokenId> = children
+                .iter()
+                .filter_map(|child| self.parents(child))
+                .flatten()
+                .collect();
+            if token_ids.is_empty() {
+                break;
+            }
+            ancestors.extend(token_ids.iter().map(|token_id| Ancestor {
+                token_id: token_id.clone(),
+                generation,
+                parents: self.parents(token_id),
+            }));
+            children = token_ids;
+        }
+        ancestors
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn parents(a: &str, b: &str) -> [TokenId; 2] {
+        [a.to_string(), b.to_string()]
+    }
+
+    #[test]
+    fn test_ancestry() {
+        let mut lineage = Lineage::new(b"l");
+        lineage.insert(&"c".to_string(), &parents("a", "b"));
+        lineage.insert(&"e".to_string(), &parents("c", "d"));
+        lineage.insert(&"f".to_string(), &parents("e", "c2"));
+
+        let ancestry = lineage.ancestry(&"f".to_string(), MAX_GENERATIONS);
+        let summary: Vec<(&str, u8, bool)> = ancestry
+            .iter()
+            .map(|a| (a.token_id.as_str(), a.generation, a.parents.is_some()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("e", 1, true),
+                ("c2", 1, false),
+                ("c", 2, true),
+                ("d", 2, false),
+                ("a", 3, false),
+                ("b", 3, false)
+            ]
+        );
+        assert_eq!(ancestry[0].parents, Some(parents("c", "d")));
+        assert_eq!(lineage.ancestry(&"f".to_string(), 1).len(), 2);
+        assert!(lineage
+            .ancestry(&"a".to_string(), MAX_GENERATIONS)
+            .is_empty());
+    }
+}
@@ -1,9 +1,8 @@
 use std::str::FromStr;
 
 use near_contract_standards::non_fungible_token::TokenId;
-use near_crypto::{ED25519PublicKey, PublicKey, Signature};
+use near_crypto::{PublicKey, Signature};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::bs58;
 use near_sdk::collections::LookupMap;
 use near_sdk::IntoStorageKey;
 
@@ -16,26 +15,35 @@ pub struct Linkdrop {
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Linkdrops {
     pending_drops: LookupMap<String, Linkdrop>,
+    /// Keys of the pending drops of each token, for drops added since the 2026-10-18 migration.
+    pub_keys_by_token_id: LookupMap<TokenId, Vec<String>>,
 }
 
 impl Linkdrops {
-    pub fn new<Q>(pending_drops_prefix: Q) -> Self
+    pub fn new<Q, R>(pending_drops_prefix: Q, pub_keys_by_token_id_prefix: R) -> Self
     where
         Q: IntoStorageKey,
+        R: IntoStorageKey,
     {
         Self {
             pending_drops: LookupMap::new(pending_drops_prefix),
+            pub_keys_by_token_id: LookupMap::new(pub_keys_by_token_id_prefix),
         }
     }
 
     pub fn add_drop(&mut self, pub_key: &String, token_id: &TokenId, approval_id: u64) {
-        self.pending_drops.insert(
+        if let Some(previous) = self.pending_drops.insert(
             pub_key,
             &Linkdrop {
                 token: token_id.clone(),
                 approval_id,
             },
-        );
+        ) {
+            self.remove_pub_key(&previous.token, pub_key);
+        }
+        let mut pub_keys = self.pub_keys_by_token_id.get(token_id).unwrap_or_default();
+        pub_keys.push(pub_key.clone());
+        self.pub_keys_by_token_id.insert(token_id, &pub_keys);
     }
 
     pub fn get_drop(&mut self, pub_key: &String, signature: String) -> Linkdrop {
@@ -44,13 +52,36 @@ impl Linkdrops {
     }
 
     pub fn remove_drop(&mut self, pub_key: &String) {
-        self.pending_drops.remove(pub_key);
+        if let Some(drop) = self.pending_drops.remove(pub_key) {
+            self.remove_pub_key(&drop.token, pub_key);
+        }
+    }
+
+    /// Remove every pending drop of `token_id`.
+    pub fn remove_token_drops(&mut self, token_id: &TokenId) {
+        for pub_key in self
+            .pub_keys_by_token_id
+            .remove(token_id)
+            .unwrap_or_default()
+        {
+            self.pending_drops.remove(&pub_key);
+        }
+    }
+
+    fn remove_pub_key(&mut self, token_id: &TokenId, pub_key: &str) {
+        let mut pub_keys = self.pub_keys_by_token_id.get(token_id).unwrap_or_default();
+        pub_keys.retain(|key| key != pub_key);
+        if pub_keys.is_empty() {
+            self.pub_keys_by_token_id.remove(token_id);
+        } else {
+            self.pub_keys_by_token_id.insert(token_id, &pub_keys);
+        }
     }
 }
 
-fn check_priv_key(pub_key_str: &String, signature_str: String) -> bool {
+fn check_priv_key(pub_key_str: &str, signature_str: String) -> bool {
     let signature: Signature = signature_str.parse().unwrap();
-    let pub_key = PublicKey::from_str(&pub_key_str).unwrap();
+    let pub_key = PublicKey::from_str(pub_key_str).unwrap();
     signature.verify(&[0u8; 32], &pub_key)
 }
 
@@ -60,4 +91,28 @@ mod tests {
 
     #[test]
     fn test_linkdrop_init() {}
+
+    #[test]
+    fn test_remove_token_drops() {
+        let mut linkdrops = Linkdrops::new(b"p", b"k");
+        let (a, b) = ("token_a".to_string(), "token_b".to_string());
+        linkdrops.add_drop(&"key_1".to_string(), &a, 1);
+        linkdrops.add_drop(&"key_2".to_string(), &a, 2);
+        linkdrops.add_drop(&"key_3".to_string(), &b, 1);
+        // Reusing a key moves it to the other token.
+        linkdrops.add_drop(&"key_2".to_string(), &b, 2);
+
+        linkdrops.remove_token_drops(&a);
+        assert!(linkdrops.pending_drops.get(&"key_1".to_string()).is_none());
+        assert!(linkdrops.pub_keys_by_token_id.get(&a).is_none());
+        assert_eq!(
+            linkdrops.pub_keys_by_token_id.get(&b),
+            Some(vec!["key_3".to_string(), "key_2".to_string()])
+        );
+
+        linkdrops.remove_drop(&"key_3".to_string());
+        linkdrops.remove_token_drops(&b);
+        assert!(linkdrops.pending_drops.get(&"key_2".to_string()).is_none());
+        assert!(linkdrops.pub_keys_by_token_id.get(&b).is_none());
+    }
 }
@@ -0,0 +1,178 @@
+use near_sdk::env;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::json;
+
+/// How the art of an edition is put into `TokenMetadata`.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum MediaEncoding {
+    /// `media` is a percent-encoded `data:image/svg+xml` URI.
+    PercentEncoded,
+    /// `media` is a `data:image/svg+xml` URI as the 2022 edition was minted with: some
+    /// characters percent-encoded, double quotes swapped for single ones.
+    LegacyPercentEncoded,
+    /// `media` is a base64 `data:image/svg+xml` URI, which more wallets display.
+    Base64,
+    /// No `media`, the raw SVG is the `image_data` of a JSON document in `reference`.
+    Reference,
+}
+
+pub const ENCODINGS: [MediaEncoding; 4] = [
+    MediaEncoding::PercentEncoded,
+    MediaEncoding::LegacyPercentEncoded,
+    MediaEncoding::Base64,
+    MediaEncoding::Reference,
+];
+
+/// `media` and `reference` of a token, with the sha256 of the content they point to.
+pub struct Media {
+    pub media: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<Base64VecU8>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MediaSize {
+    pub encoding: MediaEncoding,
+    /// Borsh size of the token metadata with this encoding.
+    pub bytes: u64,
+}
+
+pub fn encode(svg: &str, encoding: MediaEncoding) -> Media {
+    match encoding {
+        MediaEncoding::PercentEncoded => Media {
+            media: Some(crate::svg::data_uri(svg)),
+            media_hash: Some(hash(svg.as_bytes())),
+            reference: None,
+            reference_hash: None,
+        },
+        MediaEncoding::LegacyPercentEncoded => {
+            let uri = legacy_data_uri(svg);
+            // The URI doesn't decode to `svg`, hash what it does decode to.
+            let media_hash = data_uri_content(&uri).map(|content| hash(&content));
+            Media {
+                media: Some(uri),
+                media_hash,
+                reference: None,
+                reference_hash: None,
+            }
+        }
+        MediaEncoding::Base64 => Media {
+            media: Some(format!("data:image/svg+xml;base64,{}", base64::encode(svg))),
+            media_hash: Some(hash(svg.as_bytes())),
+            reference: None,
+            reference_hash: None,
+        },
+        MediaEncoding::Reference => {
+            let document = json!({ "image_data": svg }).to_string();
+            Media {
+                media: None,
+                media_hash: None,
+                reference: Some(format!(
+                    "data:application/json;base64,{}",
+                    base64::encode(&document)
+                )),
+                reference_hash: Some(hash(document.as_bytes())),
+            }
+        }
+    }
+}
+
+fn legacy_data_uri(svg: &str) -> String {
+    let encoded = svg
+        .replace('%', "%25")
+        .replace("> <", "><") // normalise spaces elements
+        .replace("; }", ";}") // normalise spaces css
+        .replace('<', "%3c")
+        .replace('>', "%3e")
+        .replace('"', "'")
+        .replace('#', "%23") // needed for ie and firefox
+        .replace('{', "%7b")
+        .replace('}', "%7d")
+        .replace('|', "%7c")
+        .replace('^', "%5e")
+        .replace('`', "%60")
+        .replace('@', "%40");
+
+    format!("{}{}", "data:image/svg+xml;charset=UTF-8,", &encoded)
+}
+
+/// sha256 of `content`, as stored in `media_hash` and `reference_hash`.
+pub fn hash(content: &[u8]) -> Base64VecU8 {
+    Base64VecU8(env::sha256(content))
+}
+
+/// Content of a percent-encoded or base64 `data:` URI, `None` for any other URI.
+pub fn data_uri_content(uri: &str) -> Option<Vec<u8>> {
+    let (header, data) = uri.strip_prefix("data:")?.split_once(',')?;
+    if header.ends_with(";base64") {
+        return base64::decode(data).ok();
+    }
+    let mut content = Vec::with_capacity(data.len());
+    let mut bytes = data.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let hex = [bytes.next()?, bytes.next()?];
+            content.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            content.push(byte);
+        }
+    }
+    Some(content)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let svg = r##"<svg fill="#fff"/>"##;
+        let media = encode(svg, MediaEncoding::Base64);
+        assert_eq!(
+            media.media.unwrap(),
+            "data:image/svg+xml;base64,PHN2ZyBmaWxsPSIjZmZmIi8+"
+        );
+        assert!(media.reference.is_none());
+
+        let media = encode(svg, MediaEncoding::Reference);
+        assert!(media.media.is_none());
+        let reference = media.reference.unwrap();
+        let document = base64::decode(
+            reference
+                .strip_prefix("data:application/json;base64,")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(document).unwrap(),
+            r##"{"image_data":"<svg fill=\"#fff\"/>"}"##
+        );
+    }
+
+    #[test]
+    fn test_hash() {
+        let svg = r##"<svg fill="#fff">月</svg>"##;
+        for encoding in ENCODINGS {
+            let media = encode(svg, encoding);
+            match media.media {
+                Some(uri) => assert_eq!(
+                    hash(&data_uri_content(&uri).unwrap()),
+                    media.media_hash.unwrap()
+                ),
+                None => assert_eq!(
+                    hash(&data_uri_content(&media.reference.unwrap()).unwrap()),
+                    media.reference_hash.unwrap()
+                ),
+            }
+        }
+        assert_eq!(
+            data_uri_content("data:image/svg+xml;charset=UTF-8,%3csvg fill='%23fff'/%3e").unwrap(),
+            b"<svg fill='#fff'/>"
+        );
+        assert!(data_uri_content("ipfs://custom").is_none());
+    }
+}
@@ -0,0 +1,68 @@
+//! Sealed gift box shown instead of the art of an edition until it is revealed.
+use crate::edition::Edition;
+use crate::palette::CLASSIC;
+use crate::svg::Element;
+
+/// The same for every token of `edition`, so it tells nothing about their art.
+pub fn svg(edition: &Edition) -> String {
+    let ribbon = CLASSIC.filling[0];
+    let rect = |x: u32, y: u32, width: u32, height: u32| {
+        Element::new("rect")
+            .attr("x", x)
+            .attr("y", y)
+            .attr("width", width)
+            .attr("height", height)
+    };
+    let bow = |cx: u32| {
+        Element::new("ellipse")
+            .attr("cx", cx)
+            .attr("cy", 318)
+            .attr("rx", 78)
+            .attr("ry", 42)
+            .attr("fill", "none")
+            .attr("stroke", ribbon)
+            .attr("stroke-width", 26)
+    };
+    Element::new("svg")
+        .attr("viewBox", "0 0 1080 1080")
+        .attr("xmlns", "http://www.w3.org/2000/svg")
+        .attr("role", "img")
+        .child(Element::new("title").text(format!("{} (sealed)", edition.title)))
+        .child(rect(0, 0, 1080, 1080).attr("fill", "#1b1530"))
+        .child(
+            rect(240, 470, 600, 410)
+                .attr("rx", 24)
+                .attr("fill", CLASSIC.crust)
+                .attr("stroke", CLASSIC.crust_stroke)
+                .attr("stroke-width", 12),
+        )
+        .child(
+            rect(210, 360, 660, 130)
+                .attr("rx", 20)
+                .attr("fill", CLASSIC.rim[1])
+                .attr("stroke", CLASSIC.crust_stroke)
+                .attr("stroke-width", 12),
+        )
+        .child(rect(510, 360, 60, 520).attr("fill", ribbon))
+        .child(rect(240, 640, 600, 50).attr("fill", ribbon))
+        .child(bow(468))
+        .child(bow(612))
+        .child(
+            Element::new("circle")
+                .attr("cx", 540)
+                .attr("cy", 665)
+                .attr("r", 72)
+                .attr("fill", "#c0161f"),
+        )
+        .child(
+            Element::new("text")
+                .attr("x", 540)
+                .attr("y", 693)
+                .attr("font-family", "serif")
+                .attr("font-size", 84)
+                .attr("text-anchor", "middle")
+                .attr("fill", "#ffd86b")
+                .text("封"),
+        )
+        .render()
+}
@@ -0,0 +1,59 @@
+//! Off-chain access to the art generators, for previews and design review. Renders exactly what
+//! the contract renders for the same seed and edition.
+use near_sdk::bs58;
+use near_sdk::serde_json::Value;
+
+use crate::generator;
+
+pub use crate::edition::{get as edition, latest as latest_edition, Edition, EDITIONS};
+pub use crate::generator::Seed;
+
+pub fn generator_name(edition: &Edition) -> &'static str {
+    generator::by_version(edition.generator).name()
+}
+
+pub fn render_svg(seed: &Seed, edition: &Edition) -> String {
+    generator::by_version(edition.generator).svg(seed, &edition.style)
+}
+
+pub fn attributes(seed: &Seed, edition: &Edition) -> Vec<(&'static str, Value)> {
+    generator::by_version(edition.generator).attributes(seed, &edition.style)
+}
+
+/// Seed and edition of a generated token id, "<edition>-<bs58 seed>". Editions revealed after
+/// minting number their tokens instead, their seeds are only known to `nft_seed`.
+pub fn token_seed(token_id: &str) -> Option<(Seed, &'static Edition)> {
+    let edition = crate::edition::of_token(token_id)?;
+    let (_, encoded) = token_id.split_once('-')?;
+    let seed = bs58::decode(encoded)
+        .with_alphabet(bs58::Alphabet::BITCOIN)
+        .into_vec()
+        .ok()?;
+    Some((Seed::from_slice(&seed)?, edition))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_seed() {
+        let seed = Seed::new([7u8; Seed::LEN]);
+        let token_id = format!(
+            "2022-{}",
+            bs58::encode(&*seed)
+                .with_alphabet(bs58::Alphabet::BITCOIN)
+                .into_string()
+        );
+        let (decoded, edition) = token_seed(&token_id).unwrap();
+        assert_eq!(decoded, seed);
+        assert_eq!(edition.id, "2022");
+        assert!(token_seed("1999-abc").is_none());
+        assert!(token_seed("2022-abc").is_none());
+        assert!(token_seed("2027-1").is_none());
+        assert_eq!(
+            render_svg(&seed, edition),
+            generator::by_version(1).svg(&seed, &generator::Style::CLASSIC)
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::Value;
+use near_sdk::{env, IntoStorageKey};
+use std::collections::BTreeMap;
+
+use crate::generator::Attributes;
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RarityView {
+    /// Sum over all traits of `supply * 100 / tokens sharing the trait value`.
+    pub score: u64,
+    /// 1 for the rarest token of the edition. Tokens with equal scores share a rank.
+    pub rank: u64,
+    pub supply: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+enum Backfill {
+    Done,
+    /// Tokens minted before the counters existed are being counted in token id order. Only
+    /// tokens up to and including this id are counted so far.
+    Until(Option<TokenId>),
+}
+
+/// Buckets are stored this many to a page, so a mint rewrites a single page.
+const BUCKET_PAGE: u32 = 32;
+
+/// Tokens of an edition sharing all their trait values, which gives them the same score.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct Bucket {
+    trait_keys: Vec<String>,
+    count: u64,
+}
+
+/// Trait distribution of every edition, updated on mint and burn.
+///
+/// Tokens are also counted by trait combination, so ranks are counted over the combinations
+/// of an edition rather than its tokens. Combinations stay once their last token is burnt.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Rarity {
+    supply_by_edition: LookupMap<String, u64>,
+    count_by_trait: LookupMap<String, u64>,
+    /// Pages of buckets, keyed by edition and page number.
+    bucket_pages: LookupMap<(String, u32), Vec<Bucket>>,
+    /// Page and slot of the bucket of a combination, keyed by the sha256 of its trait keys.
+    bucket_by_combination: LookupMap<Vec<u8>, (u32, u32)>,
+    buckets_by_edition: LookupMap<String, u32>,
+    backfill: Backfill,
+}
+
+impl Rarity {
+    pub fn new<Q, R, S>(
+        supply_by_edition_prefix: Q,
+        count_by_trait_prefix: R,
+        buckets_prefix: S,
+    ) -> Self
+    where
+        Q: IntoStorageKey,
+        R: IntoStorageKey,
+        S: IntoStorageKey,
+    {
+        let buckets_prefix = buckets_prefix.into_storage_key();
+        let prefix = |suffix: &[u8]| [&buckets_prefix[..], suffix].concat();
+        Self {
+            supply_by_edition: LookupMap::new(supply_by_edition_prefix),
+            count_by_trait: LookupMap::new(count_by_trait_prefix),
+            bucket_pages: LookupMap::new(prefix(b"p")),
+            bucket_by_combination: LookupMap::new(prefix(b"c")),
+            buckets_by_edition: LookupMap::new(prefix(b"e")),
+            backfill: Backfill::Done,
+        }
+    }
+
+    /// Counters for a contract that already has tokens, which still need to be counted with
+    /// `backfill`.
+    pub fn new_with_backfill<Q, R, S>(
+        supply_by_edition_prefix: Q,
+        count_by_trait_prefix: R,
+        buckets_prefix: S,
+    ) -> Self
+    where
+        Q: IntoStorageKey,
+        R: IntoStorageKey,
+        S: IntoStorageKey,
+    {
+        Self {
+            backfill: Backfill::Until(None),
+            ..Self::new(
+                supply_by_edition_prefix,
+                count_by_trait_prefix,
+                buckets_prefix,
+            )
+        }
+    }
+
+    pub fn add(&mut self, token_id: &TokenId, edition: &str, attributes: &Attributes) {
+        if self.is_counted(token_id) {
+            self.update(edition, attributes, |count| count + 1);
+        }
+    }
+
+    pub fn remove(&mut self, token_id: &TokenId, edition: &str, attributes: &Attributes) {
+        if self.is_counted(token_id) {
+            self.update(edition, attributes, |count| count - 1);
+        }
+    }
+
+    /// Count an already minted token, `None` for tokens without generated art. Tokens must be
+    /// passed in ascending token id order.
+    pub fn backfill(&mut self, token_id: &TokenId, traits: Option<(&str, &Attributes)>) {
+        assert!(!self.is_counted(token_id), "Token already counted");
+        if let Some((edition, attributes)) = traits {
+            self.update(edition, attributes, |count| count + 1);
+        }
+        self.backfill = Backfill::Until(Some(token_id.clone()));
+    }
+
+    /// Last counted token id of a pending backfill, or `None` if it hasn't started.
+    pub fn backfill_cursor(&self) -> Option<TokenId> {
+        match &self.backfill {
+            Backfill::Done => env::panic_str("Backfill already done"),
+            Backfill::Until(cursor) => cursor.clone(),
+        }
+    }
+
+    pub fn finish_backfill(&mut self) {
+        self.backfill = Backfill::Done;
+    }
+
+    pub fn score(&self, edition: &str, attributes: &Attributes) -> u64 {
+        let supply = self.supply(edition);
+        attributes
+            .iter()
+            .map(|(trait_type, value)| {
+                let count = self
+                    .count_by_trait
+                    .get(&trait_key(edition, trait_type, value))
+                    .unwrap_or(0);
+                supply * 100 / u64::max(count, 1)
+            })
+            .sum()
+    }
+
+    /// 1 + the number of counted tokens of `edition` scoring higher than `score`. Reads every
+    /// trait combination of the edition and the counter of every trait value, not the tokens.
+    pub fn rank(&self, edition: &str, score: u64) -> u64 {
+        let supply = self.supply(edition);
+        let buckets = self
+            .buckets_by_edition
+            .get(&edition.to_string())
+            .unwrap_or(0);
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        let mut rarer = 0;
+        for page in 0..buckets.div_ceil(BUCKET_PAGE) {
+            for bucket in self.bucket_pages.get(&(edition.to_string(), page)).unwrap() {
+                if bucket.count == 0 {
+                    continue;
+                }
+                let bucket_score: u64 = bucket
+                    .trait_keys
+                    .into_iter()
+                    .map(|key| {
+                        let count = *counts
+                            .entry(key)
+                            .or_insert_with_key(|key| self.count_by_trait.get(key).unwrap_or(0));
+                        supply * 100 / u64::max(count, 1)
+                    })
+                    .sum();
+                if bucket_score > score {
+                    rarer += bucket.count;
+                }
+            }
+        }
+        rarer + 1
+    }
+
+    pub fn supply(&self, edition: &str) -> u64 {
+        self.supply_by_edition
+            .get(&edition.to_string())
+            .unwrap_or(0)
+    }
+
+    fn is_counted(&self, token_id: &TokenId) -> bool {
+        match &self.backfill {
+            Backfill::Done => true,
+            Backfill::Until(None) => false,
+            Backfill::Until(Some(cursor)) => token_id <= cursor,
+        }
+    }
+
+    fn update(&mut self, edition: &str, attributes: &Attributes, f: impl Fn(u64) -> u64) {
+        let edition_key = edition.to_string();
+        let supply = self.supply_by_edition.get(&edition_key).unwrap_or(0);
+        self.supply_by_edition.insert(&edition_key, &f(supply));
+        let trait_keys: Vec<String> = attributes
+            .iter()
+            .map(|(trait_type, value)| trait_key(edition, trait_type, value))
+            .collect();
+        for key in &trait_keys {
+            let count = self.count_by_trait.get(key).unwrap_or(0);
+            self.count_by_trait.insert(key, &f(count));
+        }
+        self.update_bucket(edition_key, trait_keys, f);
+    }
+
+    fn update_bucket(&mut self, edition: String, trait_keys: Vec<String>, f: impl Fn(u64) -> u64) {
+        let combination = env::sha256(trait_keys.join("\n").as_bytes());
+        let (page, slot) = match self.bucket_by_combination.get(&combination) {
+            Some(position) => position,
+            None => {
+                let buckets = self.buckets_by_edition.get(&edition).unwrap_or(0);
+                self.buckets_by_edition.insert(&edition, &(buckets + 1));
+                let position = (buckets / BUCKET_PAGE, buckets % BUCKET_PAGE);
+                self.bucket_by_combination.insert(&combination, &position);
+                position
+            }
+        };
+        let key = (edition, page);
+        let mut buckets = self.bucket_pages.get(&key).unwrap_or_default();
+        match buckets.get_mut(slot as usize) {
+            Some(bucket) => bucket.count = f(bucket.count),
+            None => buckets.push(Bucket {
+                trait_keys,
+                count: f(0),
+            }),
+        }
+        self.bucket_pages.insert(&key, &buckets);
+    }
+}
+
+fn trait_key(edition: &str, trait_type: &str, value: &Value) -> String {
+    format!("{}:{}:{}", edition, trait_type, value)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn attributes(hue: &str) -> Attributes {
+        vec![("Glitch Bands", 5.into()), ("Hue Family", hue.into())]
+    }
+
+    #[test]
+    fn test_score() {
+        let mut rarity = Rarity::new(b"s", b"c", b"b");
+        rarity.add(&"2022-a".to_string(), "2022", &attributes("red"));
+        rarity.add(&"2022-b".to_string(), "2022", &attributes("red"));
+        rarity.add(&"2022-c".to_string(), "2022", &attributes("red"));
+        rarity.add(&"2022-d".to_string(), "2022", &attributes("blue"));
+        assert_eq!(rarity.supply("2022"), 4);
+        assert_eq!(rarity.score("2022", &attributes("red")), 400 / 4 + 400 / 3);
+        assert_eq!(rarity.score("2022", &attributes("blue")), 400 / 4 + 400);
+
+        rarity.remove(&"2022-d".to_string(), "2022", &attributes("blue"));
+        assert_eq!(rarity.supply("2022"), 3);
+        assert_eq!(rarity.score("2022", &attributes("red")), 300 / 3 + 300 / 3);
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut rarity = Rarity::new(b"s", b"c", b"b");
+        for i in 0..40 {
+            let color = if i < 30 { "red" } else { "blue" };
+            rarity.add(&format!("2022-{}", i), "2022", &attributes(color));
+        }
+        rarity.add(&"2022-40".to_string(), "2022", &attributes("gold"));
+        rarity.add(&"2023-0".to_string(), "2023", &attributes("red"));
+
+        let rank =
+            |rarity: &Rarity, color| rarity.rank("2022", rarity.score("2022", &attributes(color)));
+        assert_eq!(rank(&rarity, "gold"), 1);
+        assert_eq!(rank(&rarity, "blue"), 2);
+        assert_eq!(rank(&rarity, "red"), 12);
+
+        rarity.remove(&"2022-40".to_string(), "2022", &attributes("gold"));
+        rarity.remove(&"2022-39".to_string(), "2022", &attributes("blue"));
+        assert_eq!(rank(&rarity, "blue"), 1);
+        assert_eq!(rank(&rarity, "red"), 10);
+        assert_eq!(
+            rarity.rank("2023", rarity.score("2023", &attributes("red"))),
+            1
+        );
+    }
+
+    #[test]
+    fn test_backfill() {
+        let mut rarity = Rarity::new_with_backfill(b"s", b"c", b"b");
+        // Minted while the backfill is pending, counted by the backfill instead.
+        rarity.add(&"2022-b".to_string(), "2022", &attributes("red"));
+        assert_eq!(rarity.supply("2022"), 0);
+
+        assert_eq!(rarity.backfill_cursor(), None);
+        rarity.backfill(&"2022-a".to_string(), Some(("2022", &attributes("red"))));
+        rarity.backfill(&"2022-b".to_string(), Some(("2022", &attributes("red"))));
+        rarity.backfill(&"custom".to_string(), None);
+        assert_eq!(rarity.backfill_cursor(), Some("custom".to_string()));
+
+        // Sorts before the cursor, so the backfill won't see it anymore.
+        rarity.add(&"2022-a2".to_string(), "2022", &attributes("blue"));
+        rarity.finish_backfill();
+        rarity.add(&"2022-c".to_string(), "2022", &attributes("blue"));
+        assert_eq!(rarity.supply("2022"), 4);
+    }
+}
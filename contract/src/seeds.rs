@@ -0,0 +1,181 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::bs58;
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId, IntoStorageKey};
+
+use crate::generator::Seed;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokenSeed {
+    pub seed: Vec<u8>,
+    /// Version of the art generator the seed was rendered with.
+    pub version: u8,
+}
+
+impl TokenSeed {
+    /// The seed as generators take it. Minted seeds are always `Seed::LEN` bytes long.
+    pub fn art_seed(&self) -> Seed {
+        Seed::from_slice(&self.seed).unwrap_or_else(|| env::panic_str("Invalid token seed"))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenSeedView {
+    /// Base58 (bitcoin alphabet) encoded seed.
+    pub seed: String,
+    pub version: u8,
+    /// Name of the generator `version` refers to.
+    pub generator: &'static str,
+}
+
+impl From<TokenSeed> for TokenSeedView {
+    fn from(token_seed: TokenSeed) -> Self {
+        Self {
+            seed: bs58::encode(&token_seed.seed)
+                .with_alphabet(bs58::Alphabet::BITCOIN)
+                .into_string(),
+            version: token_seed.version,
+            generator: crate::generator::by_version(token_seed.version).name(),
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Seeds {
+    nonce: u64,
+    seed_by_token_id: LookupMap<TokenId, TokenSeed>,
+    serial_by_edition: LookupMap<String, u64>,
+}
+
+impl Seeds {
+    pub fn new<Q, R>(seed_by_token_id_prefix: Q, serial_by_edition_prefix: R) -> Self
+    where
+        Q: IntoStorageKey,
+        R: IntoStorageKey,
+    {
+        Self {
+            nonce: 0,
+            seed_by_token_id: LookupMap::new(seed_by_token_id_prefix),
+            serial_by_edition: LookupMap::new(serial_by_edition_prefix),
+        }
+    }
+
+    /// Number the next token of `edition`, from 1. Tokens of editions revealed after minting
+    /// are "<edition>-<serial>" rather than named after their seed, which would give the art
+    /// away.
+    pub fn next_serial(&mut self, edition: &str) -> u64 {
+        let edition = edition.to_string();
+        let serial = self.serial_by_edition.get(&edition).unwrap_or(0) + 1;
+        self.serial_by_edition.insert(&edition, &serial);
+        serial
+    }
+
+    /// Derive a fresh seed from the block seed, the mint nonce and the receiver.
+    ///
+    /// The nonce is bumped on every call, so mints sharing a block seed still get distinct seeds.
+    pub fn next(&mut self, receiver_id: &AccountId) -> Vec<u8> {
+        self.nonce += 1;
+        env::sha256(
+            &[
+                &env::random_seed()[..],
+                &self.nonce.to_le_bytes(),
+                receiver_id.as_bytes(),
+            ]
+            .concat(),
+        )
+    }
+
+    /// Derive the seed of a token baked from two parents, like `next` but mixing in the parent
+    /// seeds.
+    pub fn next_baked(&mut self, receiver_id: &AccountId, parent_seeds: &[Vec<u8>; 2]) -> Vec<u8> {
+        self.nonce += 1;
+        env::sha256(
+            &[
+                &env::random_seed()[..],
+                &self.nonce.to_le_bytes(),
+                receiver_id.as_bytes(),
+                &parent_seeds[0],
+                &parent_seeds[1],
+            ]
+            .concat(),
+        )
+    }
+
+    pub fn insert(&mut self, token_id: &TokenId, token_seed: &TokenSeed) {
+        self.seed_by_token_id.insert(token_id, token_seed);
+    }
+
+    /// Seed of `token_id`, falling back to the token id itself for tokens minted before seeds
+    /// were stored. Those are all "2022-" + bs58(seed) rendered with generator version 1.
+    pub fn get(&self, token_id: &TokenId) -> Option<TokenSeed> {
+        self.seed_by_token_id
+            .get(token_id)
+            .or_else(|| legacy_seed(token_id))
+    }
+}
+
+fn legacy_seed(token_id: &TokenId) -> Option<TokenSeed> {
+    let encoded = token_id.strip_prefix("2022-")?;
+    let seed = bs58::decode(encoded)
+        .with_alphabet(bs58::Alphabet::BITCOIN)
+        .into_vec()
+        .ok()?;
+    if seed.len() != Seed::LEN {
+        return None;
+    }
+    Some(TokenSeed { seed, version: 1 })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    fn accounts(id: usize) -> AccountId {
+        AccountId::new_unchecked(format!("test_account_{}", id))
+    }
+
+    #[test]
+    fn test_next_is_distinct() {
+        let mut seeds = Seeds::new(b"s", b"n");
+        let a = seeds.next(&accounts(0));
+        let b = seeds.next(&accounts(0));
+        let c = seeds.next(&accounts(1));
+        assert_eq!(a.len(), 32);
+        assert_ne!(a, b);
+        assert_ne!(b, c);
+        assert_eq!(seeds.nonce, 3);
+        let baked = seeds.next_baked(&accounts(0), &[a.clone(), b.clone()]);
+        let swapped = seeds.next_baked(&accounts(0), &[b, a]);
+        assert_eq!(baked.len(), 32);
+        assert_ne!(baked, swapped);
+        assert_eq!(seeds.nonce, 5);
+    }
+
+    #[test]
+    fn test_next_serial() {
+        let mut seeds = Seeds::new(b"s", b"n");
+        assert_eq!(seeds.next_serial("a"), 1);
+        assert_eq!(seeds.next_serial("a"), 2);
+        assert_eq!(seeds.next_serial("b"), 1);
+        assert_eq!(seeds.nonce, 0);
+    }
+
+    #[test]
+    fn test_legacy_seed() {
+        let seeds = Seeds::new(b"s", b"n");
+        let seed = vec![42u8; 32];
+        let token_id = format!(
+            "2022-{}",
+            bs58::encode(&seed)
+                .with_alphabet(bs58::Alphabet::BITCOIN)
+                .into_string()
+        );
+        let token_seed = seeds.get(&token_id).unwrap();
+        assert_eq!(token_seed.seed, seed);
+        assert_eq!(token_seed.version, 1);
+        assert!(seeds.get(&"2022-abc".to_string()).is_none());
+        assert!(seeds.get(&"not-a-token".to_string()).is_none());
+    }
+}
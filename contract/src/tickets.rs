@@ -0,0 +1,95 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId, BlockHeight, IntoStorageKey};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintTicket {
+    pub edition: String,
+    pub receiver_id: AccountId,
+    pub committed_at: BlockHeight,
+}
+
+/// Paid mints waiting to be revealed. The seed of a ticket is only derived on reveal, from the
+/// randomness of a block after the one it was committed in.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MintTickets {
+    next_id: u64,
+    tickets: LookupMap<u64, MintTicket>,
+}
+
+impl MintTickets {
+    pub fn new<Q>(tickets_prefix: Q) -> Self
+    where
+        Q: IntoStorageKey,
+    {
+        Self {
+            next_id: 0,
+            tickets: LookupMap::new(tickets_prefix),
+        }
+    }
+
+    pub fn commit(&mut self, edition: &str, receiver_id: AccountId) -> u64 {
+        let ticket_id = self.next_id;
+        self.next_id += 1;
+        self.tickets.insert(
+            &ticket_id,
+            &MintTicket {
+                edition: edition.to_string(),
+                receiver_id,
+                committed_at: env::block_height(),
+            },
+        );
+        ticket_id
+    }
+
+    pub fn reveal(&mut self, ticket_id: u64) -> MintTicket {
+        let ticket = self.tickets.get(&ticket_id).expect("Ticket not found");
+        assert!(
+            env::block_height() > ticket.committed_at,
+            "Ticket can only be revealed in a later block"
+        );
+        self.tickets.remove(&ticket_id);
+        ticket
+    }
+
+    pub fn get(&self, ticket_id: u64) -> Option<MintTicket> {
+        self.tickets.get(&ticket_id)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn accounts(id: usize) -> AccountId {
+        AccountId::new_unchecked(format!("test_account_{}", id))
+    }
+
+    #[test]
+    fn test_commit_reveal() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_index(10).build());
+        let mut tickets = MintTickets::new(b"t");
+        let ticket_id = tickets.commit("2022", accounts(0));
+        assert_eq!(tickets.commit("2022", accounts(1)), ticket_id + 1);
+
+        testing_env!(context.block_index(11).build());
+        let ticket = tickets.reveal(ticket_id);
+        assert_eq!(ticket.receiver_id, accounts(0));
+        assert!(tickets.get(ticket_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Ticket can only be revealed in a later block")]
+    fn test_reveal_same_block() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_index(10).build());
+        let mut tickets = MintTickets::new(b"t");
+        let ticket_id = tickets.commit("2022", accounts(0));
+        tickets.reveal(ticket_id);
+    }
+}
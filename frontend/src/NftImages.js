@@ -21,3 +21,21 @@ export const glitchFacaiUrls = [
   glitchFacaiUrl8,
   glitchFacaiUrl9,
 ];
+
+// Built from contract/preview by `yarn build:preview`: the contract's own art generators, so a
+// shaken mooncake is exactly what a mint with the same seed renders.
+import initPreview, { render, traits } from "./preview/mooncake_preview.js";
+
+const previewReady = initPreview();
+
+// Render a mooncake of `edition`, the newest one by default, from a random seed.
+export async function shakeMooncake(edition) {
+  await previewReady;
+  const seed = crypto.getRandomValues(new Uint8Array(32));
+  const svg = render(seed, edition);
+  return {
+    seed,
+    url: "data:image/svg+xml;charset=UTF-8," + encodeURIComponent(svg),
+    attributes: JSON.parse(traits(seed, edition)).attributes,
+  };
+}
@@ -2,10 +2,16 @@ import React from "react";
 import { signInWithNearWallet, nftMint2022 } from "./near-api";
 import facaiUrl from "/assets/facai.svg";
 import { nearConfig } from "./near-api";
-import { glitchFacaiUrls } from "./NftImages";
+import { shakeMooncake } from "./NftImages";
 
 export default function NftPage() {
   let [imageSrc, setImageSrc] = React.useState(facaiUrl);
+  let [attributes, setAttributes] = React.useState([]);
+  let shake = () =>
+    shakeMooncake("2022").then(({ url, attributes }) => {
+      setImageSrc((_) => url);
+      setAttributes((_) => attributes);
+    });
   let [txHash, setTxHash] = React.useState("");
   React.useEffect(() => {
     let search = window.location.search;
@@ -41,20 +47,22 @@ export default function NftPage() {
       <div className="flex flex-col lg:flex-row space-y-4 lg:space-y-0 justify-between">
         <div className="flex-1 mx-auto">
           <img
-            onMouseEnter={(_) => {
-              let i = Math.floor(Math.random() * 10);
-              setImageSrc((_) => glitchFacaiUrls[i]);
+            onMouseEnter={shake}
+            onClick={shake}
+            onMouseLeave={(_) => {
+              setImageSrc((_) => facaiUrl);
+              setAttributes((_) => []);
             }}
-            onClick={(_) => {
-              let i = Math.floor(Math.random() * 10);
-              setImageSrc((_) => glitchFacaiUrls[i]);
-            }}
-            onMouseLeave={(_) => setImageSrc((_) => facaiUrl)}
             className="h-96 w-96  border rounded-md border-white"
             src={imageSrc}
             alt=""
           />
           <div className="text-xl">恭喜发财</div>
+          <div className="text-sm text-gray-300">
+            {attributes
+              .map(({ trait_type, value }) => `${trait_type}: ${value}`)
+              .join(" · ")}
+          </div>
         </div>
         <div className="flex-1">
           <div className="text-lg mb-8">
@@ -0,0 +1,40 @@
+// Upgrade the deployed 2022 contract. Build first with `yarn build:contract`, then run from the
+// contract account with `near repl -s ./scripts/migrate_2026_10_18.js --accountId <contract>`.
+const { Contract, transactions } = nearAPI;
+const fs = require("fs");
+
+const GAS = "300000000000000";
+const PAGE = 20;
+
+// Deploy and migrate in one transaction, so the new code never sees the old state.
+await account.signAndSendTransaction({
+  receiverId: account.accountId,
+  actions: [
+    transactions.deployContract(
+      fs.readFileSync("./contract/target/wasm32-unknown-unknown/release/mooncake_nft.wasm")
+    ),
+    transactions.functionCall("migrate_2026_10_18", {}, GAS, "0"),
+  ],
+});
+
+const contract = new Contract(account, account.accountId, {
+  viewMethods: [],
+  changeMethods: [
+    "migrate_rarity_backfill",
+    "migrate_media_hash",
+    "migrate_lazy_media",
+  ],
+  sender: account,
+});
+
+while (!(await contract.migrate_rarity_backfill({ args: { limit: PAGE }, gas: GAS }))) {}
+
+for (const method of ["migrate_media_hash", "migrate_lazy_media"]) {
+  let from = null;
+  do {
+    ({ last_token_id: from } = await contract[method]({
+      args: { from_token_id: from, limit: PAGE },
+      gas: GAS,
+    }));
+  } while (from);
+}